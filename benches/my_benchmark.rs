@@ -23,6 +23,7 @@ impl<'a> Display for Input<'a> {
 fn criterion_benchmark(c: &mut Criterion) {
     let mut rng = rand::thread_rng();
     let data = (0..128).map(|_| rng.gen::<u32>()).collect::<Vec<_>>();
+    let bytes = data.iter().flat_map(|x| x.to_le_bytes()).collect::<Vec<_>>();
     let core = Core::new();
 
     let mut f = |inp: Input| {
@@ -42,6 +43,17 @@ fn criterion_benchmark(c: &mut Criterion) {
                 })
             },
         );
+        c.bench_with_input(
+            BenchmarkId::new("disassemble", &inp),
+            &bytes,
+            |b: &mut Bencher, i| {
+                b.iter(|| {
+                    for op in core.disassemble(i, 0) {
+                        let _ = black_box(op);
+                    }
+                })
+            },
+        );
     };
 
     f(Input {
@@ -60,6 +60,11 @@ const RZ_LIBRARIES: &[&str] = &[
     "rz_util",
     "rz_io",
     "rz_il",
+    "rz_crypto",
+    "rz_demangler",
+    "rz_hash",
+    "rz_search",
+    "rz_sign",
 ];
 
 fn main() -> Result<(), Box<dyn Error>> {
@@ -1,5 +1,4 @@
-use rizin_rs::wrapper::Core;
-use anyhow::Result;
+use rizin_rs::wrapper::{Core, Result};
 
 fn main() -> Result<()> {
     let core = Core::new();
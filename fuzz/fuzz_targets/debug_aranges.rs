@@ -1,8 +1,12 @@
 #![no_main]
 
 use libfuzzer_sys::fuzz_target;
-// use rizin_rs::wrapper::aranges;
-//
-// fuzz_target!(|input: &[u8]| {
-//     let _ = aranges(input,false);
-// });
+use rizin_rs::wrapper::{Dwarf, DwarfSections};
+
+fuzz_target!(|input: &[u8]| {
+    let sections = DwarfSections {
+        aranges: Some(input),
+        ..Default::default()
+    };
+    let _ = Dwarf::from_sections(sections, false).map(|dw| dw.aranges());
+});
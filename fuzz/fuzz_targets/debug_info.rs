@@ -1,9 +1,22 @@
 #![no_main]
 
+use libfuzzer_sys::arbitrary::Arbitrary;
 use libfuzzer_sys::fuzz_target;
-// use rizin_rs::wrapper::*;
-//
-// fuzz_target!(|input: InfoInput| {
-//     let mut abbrev = abbrev(&input.abbrev).unwrap();
-//     let _ = info(&input.info,false,&mut abbrev,None);
-// });
+use rizin_rs::wrapper::{Dwarf, DwarfSections};
+
+#[derive(Arbitrary, Debug)]
+struct InfoInput {
+    abbrev: Vec<u8>,
+    info: Vec<u8>,
+    big_endian: bool,
+}
+
+fuzz_target!(|input: InfoInput| {
+    let sections = DwarfSections {
+        abbrev: Some(&input.abbrev),
+        info: Some(&input.info),
+        ..Default::default()
+    };
+    let _ = Dwarf::from_sections(sections, input.big_endian)
+        .map(|dw| dw.units().flat_map(|u| u.dies()).count());
+});
@@ -1,12 +1,30 @@
 #![no_main]
 
+use libfuzzer_sys::arbitrary::Arbitrary;
 use libfuzzer_sys::fuzz_target;
-use rizin_rs::wrapper::*;
-// use rizin_rs::RzBinDwarfLineInfoMask_RZ_BIN_DWARF_LINE_INFO_MASK_LINES_ALL;
-//
-// fuzz_target!(|input: LineInput| {
-//     let mut abbrev = abbrev(&input.info.abbrev).unwrap();
-//     let mut info = info(&input.info.info,false,&mut abbrev,None).unwrap();
-//     let mut encoding = input.encoding;
-//     let _ = line(&input.line,false,&mut encoding,RzBinDwarfLineInfoMask_RZ_BIN_DWARF_LINE_INFO_MASK_LINES_ALL,&mut info);
-// });
+use rizin_rs::wrapper::{Dwarf, DwarfSections, Encoding};
+
+#[derive(Arbitrary, Debug)]
+struct LineInput {
+    abbrev: Vec<u8>,
+    info: Vec<u8>,
+    line: Vec<u8>,
+    version: u16,
+    address_size: u8,
+    is_64bit: bool,
+}
+
+fuzz_target!(|input: LineInput| {
+    let sections = DwarfSections {
+        abbrev: Some(&input.abbrev),
+        info: Some(&input.info),
+        line: Some(&input.line),
+        encoding: Some(Encoding {
+            version: input.version,
+            address_size: input.address_size,
+            is_64bit: input.is_64bit,
+        }),
+        ..Default::default()
+    };
+    let _ = Dwarf::from_sections(sections, false).map(|dw| dw.lines());
+});
@@ -6,5 +6,5 @@ use rizin_rs::wrapper::*;
 fuzz_target!(|data: &[u8]| {
     let mut bin = Bin::new();
     let bf = bin.open_slice(data);
-    let _ = bf.map(|mut x|x.dw());
+    let _ = bf.map(|mut x| x.dw().map(|dw| dw.units().count()));
 });
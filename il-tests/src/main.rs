@@ -6,14 +6,13 @@ use std::fmt;
 use std::path::PathBuf;
 use std::rc::Rc;
 
-use anyhow::{anyhow, Result};
 use bitvec::prelude::*;
 use clap::Parser;
 use hex::ToHex;
 use itertools::Itertools;
 use rand::Rng;
 
-use rizin_rs::wrapper::{AnalysisOp, Core};
+use rizin_rs::wrapper::{AnalysisOp, Core, RizinError};
 use sleigh_rs::file_to_sleigh;
 use sleigh_rs::pattern::BitConstraint;
 
@@ -24,17 +23,17 @@ struct Instruction {
 }
 
 impl Instruction {
-    fn from_bytes(core: &Core, bytes: &[u8], addr: usize) -> Result<Self> {
+    fn from_bytes(core: &Core, bytes: &[u8], addr: usize) -> rizin_rs::wrapper::Result<Self> {
         let op = core.analysis_op(bytes, addr)?;
         let mnemonic = op.mnemonic()?;
-        let bytes = &bytes[0..op.0.size as usize];
+        let bytes = &bytes[0..op.size()];
         match mnemonic.split_whitespace().next() {
-            None => Err(anyhow!("Invalid")),
-            Some(m) => Ok(Self {
+            Some(m) if !op.is_invalid() && m != "invalid" => Ok(Self {
                 bytes: Vec::from(bytes),
                 mnemonic: Rc::new(m.to_string()),
                 op,
             }),
+            _ => Err(RizinError::InvalidInstruction { addr: addr as _ }),
         }
     }
 }
@@ -49,7 +48,7 @@ impl Instruction {
                 "d \"{}\" {} {:#08x}",
                 op_str,
                 self.bytes.encode_hex::<String>(),
-                self.op.0.addr
+                self.op.addr()
             ),
         )?;
 
@@ -70,6 +69,10 @@ struct Args {
 
     #[arg(short, long, default_value = "tricore")]
     cpu: String,
+
+    /// Only emit instructions that assemble back to the same bytes
+    #[arg(long)]
+    check_asm: bool,
 }
 
 struct InstructionConstraint(Vec<BitConstraint>);
@@ -95,9 +98,15 @@ impl InstructionConstraint {
         (0..count)
             .filter_map(|_| {
                 let data = self.sample_data(rng);
-                Instruction::from_bytes(core, &data, addr).ok()
+                match Instruction::from_bytes(core, &data, addr) {
+                    Ok(x) => Some(x),
+                    Err(RizinError::InvalidInstruction { .. }) => None,
+                    Err(e) => {
+                        eprintln!("{}: {}", data.encode_hex::<String>(), e);
+                        None
+                    }
+                }
             })
-            .filter(|x| !x.mnemonic.to_lowercase().contains("invalid"))
             .collect_vec()
     }
 }
@@ -110,6 +119,9 @@ fn main() -> Result<(), Box<dyn Error>> {
         core.set("analysis.arch", &args.arch).unwrap();
         core.set("analysis.cpu", &args.cpu).unwrap();
         core.set("asm.cpu", &args.cpu).unwrap();
+        if args.check_asm {
+            core.set("asm.arch", &args.arch).unwrap();
+        }
         core
     };
 
@@ -133,6 +145,7 @@ fn main() -> Result<(), Box<dyn Error>> {
                 })
                 .collect_vec()
         })
+        .filter(|x| !args.check_asm || core.round_trips(&x.op, &x.bytes).unwrap_or(false))
         .sorted_by_key(|x| x.mnemonic.clone())
         .for_each(|x| {
             let _ = x.try_to_string(true).map(|str| println!("{}", str));
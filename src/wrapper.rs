@@ -1,20 +1,83 @@
-use std::ffi::{CStr, CString};
+use std::ffi::{c_char, c_void, CStr};
 use std::fmt::Display;
 use std::marker::PhantomData;
-use std::mem::{ManuallyDrop, size_of};
 use std::ops::{Deref, DerefMut};
-use std::path::PathBuf;
-use std::ptr::{addr_of, addr_of_mut, null_mut, NonNull};
+use std::ptr::{addr_of, addr_of_mut, NonNull};
 use std::{fmt, result, slice};
 
-use anyhow::anyhow;
-
 use crate::*;
 
-pub type Result<T> = anyhow::Result<T>;
-
+mod analysis;
+mod asm;
+mod bin;
+mod cmd;
+mod config;
+mod demangle;
+mod dwarf;
+mod error;
+mod flag;
+mod hash;
+mod il;
+mod io;
+mod op;
+mod plugin;
+mod pool;
+mod reg;
+mod search;
+mod sign;
+mod strings;
+mod sweep;
+mod types;
+mod vm;
+mod xref;
+
+pub use analysis::*;
+pub use asm::*;
+pub use bin::*;
+pub use cmd::*;
+pub use config::*;
+pub use demangle::*;
+pub use dwarf::*;
+pub use error::*;
+pub use flag::*;
+pub use hash::*;
+pub use il::*;
+pub use io::*;
+pub use op::*;
+pub use plugin::*;
+pub use pool::*;
+pub use reg::*;
+pub use search::*;
+pub use sign::*;
+pub use strings::*;
+pub use sweep::*;
+pub use types::*;
+pub use vm::*;
+pub use xref::*;
+
+pub type Result<T> = result::Result<T, RizinError>;
+
+/// Copies a nullable C string into an owned `String`, replacing invalid UTF-8.
+pub(crate) unsafe fn cstr_to_string(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(ptr).to_string_lossy().into_owned())
+    }
+}
+
+/// Views the elements of an `RzVector` embedded in a struct owned by rizin.
+pub(crate) unsafe fn vector_slice<T>(v: &RzVector) -> &[T] {
+    if v.a.is_null() {
+        &[]
+    } else {
+        slice::from_raw_parts(v.a as *const T, v.len)
+    }
+}
+
+/// A rizin core. Its methods mutate C state behind `&self`, so it may move
+/// between threads but not be shared, see [`CorePool`] for parallel work.
 pub struct Core(pub NonNull<RzCore>);
-unsafe impl Sync for Core {}
 unsafe impl Send for Core {}
 
 impl Drop for Core {
@@ -71,16 +134,16 @@ impl Drop for StrBuf {
 impl AnalysisOp {
     pub fn mnemonic(&self) -> Result<&str> {
         if self.0.mnemonic.is_null() {
-            Err(anyhow!("mnemonic is_null"))
+            Err(RizinError::NullPointer { what: "mnemonic" })
         } else {
             let cstr = unsafe { CStr::from_ptr(self.0.mnemonic) };
-            cstr.to_str().map_err(|_| anyhow!("invalid cstr"))
+            Ok(cstr.to_str()?)
         }
     }
 
     pub fn il_str(&self, pretty: bool) -> Result<String> {
         if self.0.il_op.is_null() {
-            Err(anyhow!("il_op is null"))
+            Err(RizinError::NullPointer { what: "il_op" })
         } else {
             let mut sb = StrBuf::new();
             unsafe {
@@ -98,6 +161,10 @@ impl Core {
     }
 
     pub fn analysis_op(&self, bytes: &[u8], addr: usize) -> Result<AnalysisOp> {
+        self.analysis_op_with(bytes, addr, OpMask::DISASM | OpMask::IL)
+    }
+
+    pub fn analysis_op_with(&self, bytes: &[u8], addr: usize, mask: OpMask) -> Result<AnalysisOp> {
         let mut op: AnalysisOp = AnalysisOp(Default::default());
         let res = unsafe {
             rz_analysis_op(
@@ -106,112 +173,81 @@ impl Core {
                 addr as _,
                 bytes.as_ptr() as _,
                 bytes.len() as _,
-                RzAnalysisOpMask_RZ_ANALYSIS_OP_MASK_DISASM
-                    | RzAnalysisOpMask_RZ_ANALYSIS_OP_MASK_IL,
+                mask.bits(),
             )
         };
         if res <= 0 {
-            Err(anyhow!("failed analysis op"))
+            Err(RizinError::InvalidInstruction { addr: addr as _ })
         } else {
             Ok(op)
         }
     }
-
-    pub fn set(&self, k: &str, v: &str) -> Result<&Self> {
-        let node = unsafe {
-            rz_config_set(
-                self.0.as_ref().config,
-                CString::new(k)?.as_ptr(),
-                CString::new(v)?.as_ptr(),
-            )
-        };
-        NonNull::new(node)
-            .map(|_| self)
-            .ok_or(anyhow!("{} is null", k))
-    }
 }
 
-pub struct BinFile<'a> {
-    core: &'a Core,
-    pub bf: NonNull<RzBinFile>,
+/// An `RzList` freed on drop, along with its elements if the list has a free
+/// function. Lists owned by rizin are wrapped in a [`ListRef`] instead.
+pub struct List<T> {
+    pub inner: NonNull<RzList>,
+    marker: PhantomData<T>,
 }
 
-impl Core {
-    unsafe fn open(&mut self, path: PathBuf) -> Result<BinFile> {
-        let mut rz_bin_opt = RzBinOptions::default();
-        rz_bin_options_init(&mut rz_bin_opt, 0, 0, 0, false);
-        let cpath = CString::new(path.to_str().unwrap()).unwrap();
-        let bf = rz_bin_open(self.0.as_ref().bin, cpath.as_ptr(), &mut rz_bin_opt);
-        Ok(BinFile {
-            core: self,
-            bf: NonNull::new(bf).ok_or(anyhow!("failed open {}", path.to_str().unwrap()))?,
-        })
-    }
+/// A borrowed `RzList`, e.g. one embedded in a rizin struct.
+pub struct ListRef<'a, T> {
+    pub inner: NonNull<RzList>,
+    marker: PhantomData<&'a T>,
 }
 
-impl Drop for BinFile<'_> {
-    fn drop(&mut self) {
-        unsafe {
-            rz_bin_file_delete(self.core.0.as_ref().bin, self.bf.as_ptr());
-        }
-    }
+/// Iterator over the elements of a list, null elements are skipped.
+pub struct ListIter<'a, T: 'a> {
+    head: Option<NonNull<RzListIter>>,
+    tail: Option<NonNull<RzListIter>>,
+    len: usize,
+    marker: PhantomData<&'a T>,
 }
 
-impl RzBinEndianReader {
-    fn new(input: &[u8], big_endian: bool) -> Self {
-        Self {
-            data: input.as_ptr() as _,
-            owned: false,
-            length: input.len() as _,
-            offset: 0,
-            big_endian,
-            relocations: null_mut(),
+impl<T> List<T> {
+    pub fn as_list_ref(&self) -> ListRef<'_, T> {
+        ListRef {
+            inner: self.inner,
+            marker: PhantomData,
         }
     }
-}
-
-pub struct DwarfAbbrev(pub NonNull<RzBinDwarfAbbrev>);
 
-impl DwarfAbbrev {
-    pub fn new(input: &[u8]) -> Result<DwarfAbbrev> {
-        let R = RzBinEndianReader::new(input, false);
-        let abbrev = unsafe {
-            let ptr = libc::malloc(size_of::<RzBinEndianReader>());
-            libc::memcpy(ptr, addr_of!(R) as _, size_of::<RzBinEndianReader>());
-            rz_bin_dwarf_abbrev_new(ptr as _)
-        };
-        NonNull::<RzBinDwarfAbbrev>::new(abbrev)
-            .map(DwarfAbbrev)
-            .ok_or(anyhow!("failed new"))
+    pub fn len(&self) -> usize {
+        self.as_list_ref().len()
     }
-}
 
-impl Drop for DwarfAbbrev {
-    fn drop(&mut self) {
-        unsafe {
-            rz_bin_dwarf_abbrev_free(self.0.as_ptr());
-        }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
-}
 
-pub struct List<T> {
-    pub inner: NonNull<RzList>,
-    marker: PhantomData<T>,
+    pub fn iter(&self) -> ListIter<'_, T> {
+        self.as_list_ref().iter()
+    }
 }
 
-pub struct ListIter<'a, T: 'a> {
-    head: Option<NonNull<RzListIter>>,
-    tail: Option<NonNull<RzListIter>>,
-    len: usize,
-    marker: PhantomData<&'a T>,
-}
+impl<'a, T> ListRef<'a, T> {
+    /// Borrows `list`, `None` if it is null.
+    ///
+    /// # Safety
+    ///
+    /// `list` must hold `T` elements and outlive `'a`.
+    pub unsafe fn from_raw(list: *const RzList) -> Option<Self> {
+        NonNull::new(list as *mut RzList).map(|inner| Self {
+            inner,
+            marker: PhantomData,
+        })
+    }
 
-impl<T> List<T> {
     pub fn len(&self) -> usize {
         unsafe { rz_list_length(self.inner.as_ptr()) as _ }
     }
 
-    pub fn iter(&self) -> ListIter<'_, T> {
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn iter(&self) -> ListIter<'a, T> {
         unsafe {
             ListIter {
                 head: NonNull::new(self.inner.as_ref().head),
@@ -223,13 +259,20 @@ impl<T> List<T> {
     }
 }
 
+impl<T> Clone for ListRef<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ListRef<'_, T> {}
+
 impl<T> TryFrom<*mut RzList> for List<T> {
     type Error = ();
 
     fn try_from(value: *mut RzList) -> result::Result<Self, Self::Error> {
-        let inner = NonNull::new(value).unwrap();
         Ok(Self {
-            inner,
+            inner: NonNull::new(value).ok_or(())?,
             marker: PhantomData,
         })
     }
@@ -243,47 +286,74 @@ impl<T> Drop for List<T> {
     }
 }
 
+impl<'a, T> IntoIterator for &'a List<T> {
+    type Item = &'a T;
+    type IntoIter = ListIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for ListRef<'a, T> {
+    type Item = &'a T;
+    type IntoIter = ListIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 impl<'a, T> Iterator for ListIter<'a, T> {
-    type Item = *mut T;
+    type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.len == 0 {
-            None
-        } else {
-            self.head.map(|node| unsafe {
-                let item = node.as_ref().elem as Self::Item;
-                self.len -= 1;
+        while self.len > 0 {
+            let node = self.head?;
+            self.len -= 1;
+            unsafe {
                 self.head = NonNull::new(rz_list_iter_get_next(node.as_ptr()));
-                item
-            })
+                if let Some(item) = (node.as_ref().elem as *const T).as_ref() {
+                    return Some(item);
+                }
+            }
         }
+        None
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.len, Some(self.len))
+        (0, Some(self.len))
     }
 }
 
 impl<'a, T> DoubleEndedIterator for ListIter<'a, T> {
     fn next_back(&mut self) -> Option<Self::Item> {
-        if self.len == 0 {
-            None
-        } else {
-            self.tail.map(|node| unsafe {
-                let item = node.as_ref().elem as Self::Item;
-                self.len -= 1;
-                self.head = NonNull::new(rz_list_iter_get_prev(node.as_ptr()));
-                item
-            })
+        while self.len > 0 {
+            let node = self.tail?;
+            self.len -= 1;
+            unsafe {
+                self.tail = NonNull::new(rz_list_iter_get_prev(node.as_ptr()));
+                if let Some(item) = (node.as_ref().elem as *const T).as_ref() {
+                    return Some(item);
+                }
+            }
         }
+        None
     }
 }
 
+/// An `RzVector` freed on drop.
 pub struct Vector<T> {
     pub(crate) inner: NonNull<RzVector>,
     marker: PhantomData<T>,
 }
 
+/// A borrowed `RzVector`.
+pub struct VectorRef<'a, T> {
+    pub(crate) inner: NonNull<RzVector>,
+    marker: PhantomData<&'a T>,
+}
+
 impl<T> Vector<T> {
     pub fn as_mut_ptr(&self) -> *mut T {
         unsafe { self.inner.as_ref().a as _ }
@@ -292,6 +362,51 @@ impl<T> Vector<T> {
     pub fn len(&self) -> usize {
         unsafe { self.inner.as_ref().len }
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn as_vector_ref(&self) -> VectorRef<'_, T> {
+        VectorRef {
+            inner: self.inner,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> VectorRef<'a, T> {
+    /// Borrows `v`, `None` if it is null.
+    ///
+    /// # Safety
+    ///
+    /// `v` must hold `T` elements and outlive `'a`.
+    pub unsafe fn from_raw(v: *const RzVector) -> Option<Self> {
+        NonNull::new(v as *mut RzVector).map(|inner| Self {
+            inner,
+            marker: PhantomData,
+        })
+    }
+
+    pub fn as_slice(&self) -> &'a [T] {
+        unsafe { vector_slice(self.inner.as_ref()) }
+    }
+}
+
+impl<T> Clone for VectorRef<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for VectorRef<'_, T> {}
+
+impl<T> Deref for VectorRef<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
 }
 
 impl<T> TryFrom<*mut RzVector> for Vector<T> {
@@ -315,13 +430,18 @@ impl<T> Deref for Vector<T> {
     type Target = [T];
 
     fn deref(&self) -> &Self::Target {
-        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len()) }
+        unsafe { vector_slice(self.inner.as_ref()) }
     }
 }
 
 impl<T> DerefMut for Vector<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        self.as_mut()
+        let len = self.len();
+        if len == 0 {
+            &mut []
+        } else {
+            unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), len) }
+        }
     }
 }
 
@@ -333,21 +453,104 @@ impl<T> Drop for Vector<T> {
     }
 }
 
+/// An `RzPVector` owning its elements, which it frees on drop with the free
+/// function given to `rz_pvector_new`.
 pub struct PVector<T> {
     pub(crate) inner: NonNull<RzPVector>,
-    pub(crate) v: ManuallyDrop<Vector<*mut T>>,
     marker: PhantomData<T>,
 }
 
+/// A borrowed `RzPVector`.
+pub struct PVectorRef<'a, T> {
+    pub(crate) inner: NonNull<RzPVector>,
+    marker: PhantomData<&'a T>,
+}
+
+/// Iterator over the elements of a pvector, null elements are skipped.
+pub struct PVectorIter<'a, T> {
+    ptrs: slice::Iter<'a, *mut T>,
+}
+
+impl<T> PVector<T> {
+    pub fn as_pvector_ref(&self) -> PVectorRef<'_, T> {
+        PVectorRef {
+            inner: self.inner,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.as_pvector_ref().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, i: usize) -> Option<&T> {
+        self.as_pvector_ref().get(i)
+    }
+
+    pub fn iter(&self) -> PVectorIter<'_, T> {
+        self.as_pvector_ref().iter()
+    }
+
+    /// The element pointers, null ones included.
+    pub fn as_ptrs(&self) -> &[*mut T] {
+        self.as_pvector_ref().as_ptrs()
+    }
+}
+
+impl<'a, T> PVectorRef<'a, T> {
+    /// Borrows `v`, `None` if it is null.
+    ///
+    /// # Safety
+    ///
+    /// `v` must hold pointers to `T` and outlive `'a`.
+    pub unsafe fn from_raw(v: *const RzPVector) -> Option<Self> {
+        NonNull::new(v as *mut RzPVector).map(|inner| Self {
+            inner,
+            marker: PhantomData,
+        })
+    }
+
+    pub fn as_ptrs(&self) -> &'a [*mut T] {
+        unsafe { vector_slice(&(*self.inner.as_ptr()).v) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.as_ptrs().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, i: usize) -> Option<&'a T> {
+        unsafe { (*self.as_ptrs().get(i)?).as_ref() }
+    }
+
+    pub fn iter(&self) -> PVectorIter<'a, T> {
+        PVectorIter {
+            ptrs: self.as_ptrs().iter(),
+        }
+    }
+}
+
+impl<T> Clone for PVectorRef<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for PVectorRef<'_, T> {}
+
 impl<T> TryFrom<*mut RzPVector> for PVector<T> {
     type Error = ();
 
     fn try_from(value: *mut RzPVector) -> result::Result<Self, Self::Error> {
-        let inner = NonNull::new(value).ok_or(())?;
-        let v = unsafe { Vector::try_from(addr_of_mut!((*value).v))? };
         Ok(Self {
-            inner,
-            v: ManuallyDrop::new(v),
+            inner: NonNull::new(value).ok_or(())?,
             marker: PhantomData,
         })
     }
@@ -361,29 +564,311 @@ impl<T> Drop for PVector<T> {
     }
 }
 
-impl<T> AsMut<Vector<*mut T>> for PVector<T> {
-    fn as_mut(&mut self) -> &mut Vector<*mut T> {
-        &mut self.v
+impl<'a, T> IntoIterator for &'a PVector<T> {
+    type Item = &'a T;
+    type IntoIter = PVectorIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
-impl<T> Deref for PVector<T> {
-    type Target = Vector<*mut T>;
+impl<'a, T> IntoIterator for PVectorRef<'a, T> {
+    type Item = &'a T;
+    type IntoIter = PVectorIter<'a, T>;
 
-    fn deref(&self) -> &Self::Target {
-        &self.v
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
-impl<T> DerefMut for PVector<T> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        self.as_mut()
+impl<'a, T> Iterator for PVectorIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.ptrs.find_map(|p| unsafe { (*p).as_ref() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.ptrs.len()))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for PVectorIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.ptrs.rfind(|p| !p.is_null()).map(|p| unsafe { &**p })
+    }
+}
+
+unsafe extern "C" fn collect_entry(user: *mut c_void, k: *const c_void, v: *const c_void) -> bool {
+    let entries = &mut *(user as *mut Vec<(*const c_void, *const c_void)>);
+    entries.push((k, v));
+    true
+}
+
+/// An `HtPP` mapping pointers to `K` to pointers to `V`, freed on drop along
+/// with whatever its free functions release.
+pub struct PtrTable<K, V> {
+    pub inner: NonNull<HtPP>,
+    marker: PhantomData<(K, V)>,
+}
+
+/// A borrowed `HtPP`.
+pub struct PtrTableRef<'a, K, V> {
+    pub inner: NonNull<HtPP>,
+    marker: PhantomData<(&'a K, &'a V)>,
+}
+
+impl<K, V> PtrTable<K, V> {
+    pub fn as_table_ref(&self) -> PtrTableRef<'_, K, V> {
+        PtrTableRef {
+            inner: self.inner,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.as_table_ref().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.as_table_ref().get(key)
+    }
+
+    pub fn entries(&self) -> Vec<(&K, &V)> {
+        self.as_table_ref().entries()
+    }
+}
+
+impl<'a, K, V> PtrTableRef<'a, K, V> {
+    /// Borrows `ht`, `None` if it is null.
+    ///
+    /// # Safety
+    ///
+    /// `ht` must map pointers to `K` to pointers to `V` and outlive `'a`.
+    pub unsafe fn from_raw(ht: *const HtPP) -> Option<Self> {
+        NonNull::new(ht as *mut HtPP).map(|inner| Self {
+            inner,
+            marker: PhantomData,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        unsafe { self.inner.as_ref().count as _ }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Looks `key` up by address, unless the table hashes key contents.
+    pub fn get(&self, key: &K) -> Option<&'a V> {
+        let mut found = false;
+        unsafe {
+            let v = ht_pp_find(
+                self.inner.as_ptr(),
+                key as *const K as _,
+                addr_of_mut!(found),
+            );
+            if found {
+                (v as *const V).as_ref()
+            } else {
+                None
+            }
+        }
+    }
+
+    /// The entries in table order, those with a null key or value are skipped.
+    pub fn entries(&self) -> Vec<(&'a K, &'a V)> {
+        let mut entries: Vec<(*const c_void, *const c_void)> = vec![];
+        unsafe {
+            ht_pp_foreach(
+                self.inner.as_ptr(),
+                Some(collect_entry),
+                addr_of_mut!(entries) as _,
+            );
+            entries
+                .into_iter()
+                .filter_map(|(k, v)| Some(((k as *const K).as_ref()?, (v as *const V).as_ref()?)))
+                .collect()
+        }
+    }
+}
+
+impl<K, V> Clone for PtrTableRef<'_, K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for PtrTableRef<'_, K, V> {}
+
+impl<K, V> TryFrom<*mut HtPP> for PtrTable<K, V> {
+    type Error = ();
+
+    fn try_from(value: *mut HtPP) -> result::Result<Self, Self::Error> {
+        Ok(Self {
+            inner: NonNull::new(value).ok_or(())?,
+            marker: PhantomData,
+        })
+    }
+}
+
+impl<K, V> Drop for PtrTable<K, V> {
+    fn drop(&mut self) {
+        unsafe {
+            ht_pp_free(self.inner.as_ptr());
+        }
+    }
+}
+
+/// An `RzSkipList` freed on drop, along with its elements if the list has a
+/// free function.
+pub struct SkipList<T> {
+    pub inner: NonNull<RzSkipList>,
+    marker: PhantomData<T>,
+}
+
+/// A borrowed `RzSkipList`.
+pub struct SkipListRef<'a, T> {
+    pub inner: NonNull<RzSkipList>,
+    marker: PhantomData<&'a T>,
+}
+
+/// Iterator over the elements of a skip list in order, null elements are
+/// skipped.
+pub struct SkipListIter<'a, T> {
+    head: *mut RzSkipListNode,
+    node: *mut RzSkipListNode,
+    marker: PhantomData<&'a T>,
+}
+
+impl<T> SkipList<T> {
+    pub fn as_skiplist_ref(&self) -> SkipListRef<'_, T> {
+        SkipListRef {
+            inner: self.inner,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.as_skiplist_ref().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn iter(&self) -> SkipListIter<'_, T> {
+        self.as_skiplist_ref().iter()
+    }
+}
+
+impl<'a, T> SkipListRef<'a, T> {
+    /// Borrows `list`, `None` if it is null.
+    ///
+    /// # Safety
+    ///
+    /// `list` must hold `T` elements and outlive `'a`.
+    pub unsafe fn from_raw(list: *const RzSkipList) -> Option<Self> {
+        NonNull::new(list as *mut RzSkipList).map(|inner| Self {
+            inner,
+            marker: PhantomData,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        unsafe { self.inner.as_ref().size as _ }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn iter(&self) -> SkipListIter<'a, T> {
+        unsafe {
+            let head = self.inner.as_ref().head;
+            SkipListIter {
+                head,
+                node: head.as_ref().map_or(head, |h| *h.forward),
+                marker: PhantomData,
+            }
+        }
+    }
+}
+
+impl<T> Clone for SkipListRef<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SkipListRef<'_, T> {}
+
+impl<T> TryFrom<*mut RzSkipList> for SkipList<T> {
+    type Error = ();
+
+    fn try_from(value: *mut RzSkipList) -> result::Result<Self, Self::Error> {
+        Ok(Self {
+            inner: NonNull::new(value).ok_or(())?,
+            marker: PhantomData,
+        })
+    }
+}
+
+impl<T> Drop for SkipList<T> {
+    fn drop(&mut self) {
+        unsafe {
+            rz_skiplist_free(self.inner.as_ptr());
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SkipList<T> {
+    type Item = &'a T;
+    type IntoIter = SkipListIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for SkipListRef<'a, T> {
+    type Item = &'a T;
+    type IntoIter = SkipListIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> Iterator for SkipListIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The last node links back to the head.
+        while !self.node.is_null() && self.node != self.head {
+            unsafe {
+                let node = &*self.node;
+                self.node = *node.forward;
+                if let Some(item) = (node.data as *const T).as_ref() {
+                    return Some(item);
+                }
+            }
+        }
+        None
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::ffi::{c_int, c_void};
     use std::mem::size_of;
+    use std::ptr::null_mut;
 
     use crate::wrapper::*;
 
@@ -408,30 +893,108 @@ mod tests {
     }
     #[test]
     fn test_pvector() {
+        let values = (0..10).collect::<Vec<i32>>();
         let vec = unsafe {
+            // Without a free function the elements stay ours.
             let x = rz_pvector_new(None);
-            for i in 0..10 {
-                rz_vector_push(addr_of_mut!((*x).v), addr_of!(i) as _);
+            for i in &values {
+                rz_pvector_push(x, i as *const i32 as _);
             }
             PVector::<i32>::try_from(x).unwrap()
         };
-        assert_eq!(
-            vec.iter().map(|x| *x as i32).collect::<Vec<i32>>(),
-            (0..10).into_iter().collect::<Vec<i32>>()
-        );
+        assert_eq!(vec.iter().copied().collect::<Vec<i32>>(), values);
+        assert_eq!(vec.get(3), Some(&3));
+        let view = unsafe { PVectorRef::<i32>::from_raw(vec.inner.as_ptr()) }.unwrap();
+        assert_eq!(view.iter().rev().next(), Some(&9));
     }
+
     #[test]
     fn test_list() {
+        let values = (0..10).collect::<Vec<i32>>();
         let list = unsafe {
             let x = rz_list_new();
-            for i in 0..10 {
-                rz_list_push(x, i as _);
+            for i in &values {
+                rz_list_append(x, i as *const i32 as _);
             }
             List::<i32>::try_from(x).unwrap()
         };
+        assert_eq!(list.iter().copied().collect::<Vec<i32>>(), values);
         assert_eq!(
-            list.iter().map(|x| x as i32).collect::<Vec<i32>>(),
-            (0..10).into_iter().collect::<Vec<i32>>()
+            list.iter().rev().copied().collect::<Vec<i32>>(),
+            values.iter().rev().copied().collect::<Vec<i32>>()
+        );
+        let mut iter = list.iter();
+        assert_eq!((iter.next(), iter.next_back()), (Some(&0), Some(&9)));
+        assert_eq!(iter.count(), 8);
+    }
+
+    #[test]
+    fn test_borrowed_views() {
+        let values = (0..4).collect::<Vec<i32>>();
+        let (list, vec) = unsafe {
+            let l = rz_list_new();
+            let v = rz_vector_new(size_of::<i32>(), None, null_mut());
+            for i in &values {
+                rz_list_append(l, i as *const i32 as _);
+                rz_vector_push(v, i as *const i32 as _);
+            }
+            (
+                List::<i32>::try_from(l).unwrap(),
+                Vector::<i32>::try_from(v).unwrap(),
+            )
+        };
+        let list_ref = unsafe { ListRef::<i32>::from_raw(list.inner.as_ptr()) }.unwrap();
+        assert_eq!(list_ref.len(), 4);
+        assert_eq!(
+            list_ref.iter().rev().copied().collect::<Vec<_>>(),
+            [3, 2, 1, 0]
         );
+        let vec_ref = unsafe { VectorRef::<i32>::from_raw(vec.inner.as_ptr()) }.unwrap();
+        assert_eq!(vec_ref.as_slice(), values.as_slice());
+        assert!(unsafe { ListRef::<i32>::from_raw(null_mut()) }.is_none());
+        // Dropping the views leaves the containers to their owners.
+        drop((list_ref, vec_ref));
+        assert_eq!(list.len() + vec.len(), 8);
+    }
+
+    #[test]
+    fn test_ptr_table() {
+        let keys = [1i32, 2, 3];
+        let values = [10i32, 20, 30];
+        let table = unsafe {
+            // Zeroed options compare and hash keys by address, and free nothing.
+            let mut opt = HtPPOptions::default();
+            let ht = ht_pp_new_opt(addr_of_mut!(opt));
+            for (k, v) in keys.iter().zip(&values) {
+                ht_pp_insert(ht, k as *const i32 as _, v as *const i32 as _);
+            }
+            PtrTable::<i32, i32>::try_from(ht).unwrap()
+        };
+        assert_eq!(table.len(), 3);
+        assert_eq!(table.get(&keys[1]), Some(&20));
+        assert_eq!(table.get(&2), None);
+        let mut entries = table.entries();
+        entries.sort();
+        assert_eq!(entries, [(&1, &10), (&2, &20), (&3, &30)]);
+    }
+
+    unsafe extern "C" fn cmp_i32(a: *const c_void, b: *const c_void, _: *mut c_void) -> c_int {
+        (*(a as *const i32)).cmp(&*(b as *const i32)) as c_int
+    }
+
+    #[test]
+    fn test_skiplist() {
+        let values = [5i32, 1, 4, 2, 3];
+        let list = unsafe {
+            let sl = rz_skiplist_new(None, Some(cmp_i32));
+            for v in &values {
+                rz_skiplist_insert(sl, v as *const i32 as _);
+            }
+            SkipList::<i32>::try_from(sl).unwrap()
+        };
+        assert_eq!(list.len(), 5);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), [1, 2, 3, 4, 5]);
+        let view = unsafe { SkipListRef::<i32>::from_raw(list.inner.as_ptr()) }.unwrap();
+        assert_eq!(view.iter().count(), 5);
     }
 }
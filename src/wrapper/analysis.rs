@@ -0,0 +1,305 @@
+use std::ptr::{null, NonNull};
+
+use crate::wrapper::{
+    cstr_to_string, opt_addr, Core, List, ListRef, PVectorRef, Result, RizinError,
+};
+use crate::*;
+
+/// How much auto-analysis [`Core::analyze`] runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum AnalysisLevel {
+    /// Like `aa`.
+    #[default]
+    Simple,
+    /// Like `aaa`.
+    Deep,
+    /// Like `aaaa`.
+    Experimental,
+}
+
+impl From<AnalysisLevel> for RzCoreAnalysisType {
+    fn from(value: AnalysisLevel) -> Self {
+        match value {
+            AnalysisLevel::Simple => RzCoreAnalysisType_RZ_CORE_ANALYSIS_SIMPLE,
+            AnalysisLevel::Deep => RzCoreAnalysisType_RZ_CORE_ANALYSIS_DEEP,
+            AnalysisLevel::Experimental => RzCoreAnalysisType_RZ_CORE_ANALYSIS_EXPERIMENTAL,
+        }
+    }
+}
+
+/// A function known to the analysis of a [`Core`].
+///
+/// Whatever can free or rewrite a function, such as [`Core::analyze`],
+/// [`Core::cmd`] or [`Core::set_function_signature`], takes `&mut Core`, so
+/// no handle outlives its function.
+#[derive(Clone, Copy)]
+pub struct Function<'a> {
+    pub(crate) core: &'a Core,
+    pub fcn: NonNull<RzAnalysisFunction>,
+}
+
+/// A basic block of a [`Function`].
+#[derive(Clone, Copy)]
+pub struct BasicBlock<'a> {
+    fcn: Function<'a>,
+    pub bb: NonNull<RzAnalysisBlock>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SwitchCase {
+    pub value: u64,
+    pub jump: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum VarStorage {
+    Reg(String),
+    /// Offset from the stack pointer at function entry.
+    Stack(i64),
+    Other,
+}
+
+/// A local variable or argument of a [`Function`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Variable {
+    pub name: String,
+    pub type_name: Option<String>,
+    pub storage: VarStorage,
+    pub is_arg: bool,
+}
+
+impl Core {
+    fn analysis(&self) -> *mut RzAnalysis {
+        unsafe { self.0.as_ref().analysis }
+    }
+
+    /// Runs auto-analysis over the loaded file.
+    ///
+    /// Takes `&mut self` so that no [`Function`] outlives the analysis rewriting it.
+    pub fn analyze(&mut self, level: AnalysisLevel) {
+        unsafe {
+            rz_core_perform_auto_analysis(self.0.as_ptr(), level.into());
+        }
+    }
+
+    /// Analyzes the function at `addr` and the functions it calls.
+    pub fn analyze_function(&mut self, addr: u64) -> Result<()> {
+        let ok = unsafe { rz_core_analysis_function_add(self.0.as_ptr(), null(), addr, true) };
+        if ok {
+            Ok(())
+        } else {
+            Err(RizinError::Analysis { addr })
+        }
+    }
+
+    pub fn functions(&self) -> Vec<Function<'_>> {
+        let fcns = unsafe { rz_analysis_function_list(self.analysis()) };
+        // The list belongs to the analysis.
+        unsafe { ListRef::<RzAnalysisFunction>::from_raw(fcns) }
+            .map(|l| {
+                l.iter()
+                    .map(|fcn| Function {
+                        core: self,
+                        fcn: NonNull::from(fcn),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The function starting at `addr`.
+    pub fn function_at(&self, addr: u64) -> Option<Function<'_>> {
+        let fcn = unsafe { rz_analysis_get_function_at(self.analysis(), addr) };
+        NonNull::new(fcn).map(|fcn| Function { core: self, fcn })
+    }
+
+    /// The functions containing `addr`.
+    pub fn functions_in(&self, addr: u64) -> Vec<Function<'_>> {
+        let fcns = unsafe { rz_analysis_get_functions_in(self.analysis(), addr) };
+        // Only the list is owned, the functions belong to the analysis.
+        List::<RzAnalysisFunction>::try_from(fcns)
+            .map(|l| {
+                l.iter()
+                    .map(|fcn| Function {
+                        core: self,
+                        fcn: NonNull::from(fcn),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl<'a> Function<'a> {
+    fn raw(&self) -> &'a RzAnalysisFunction {
+        unsafe { &*self.fcn.as_ptr() }
+    }
+
+    pub fn name(&self) -> String {
+        unsafe { cstr_to_string(self.raw().name) }.unwrap_or_default()
+    }
+
+    pub fn addr(&self) -> u64 {
+        self.raw().addr
+    }
+
+    /// Sum of the sizes of the basic blocks.
+    pub fn size(&self) -> u64 {
+        unsafe { rz_analysis_function_realsize(self.fcn.as_ptr()) }
+    }
+
+    pub fn calling_convention(&self) -> Option<String> {
+        unsafe { cstr_to_string(self.raw().cc) }
+    }
+
+    pub fn blocks(&self) -> Vec<BasicBlock<'a>> {
+        // The blocks belong to the function.
+        unsafe { PVectorRef::<RzAnalysisBlock>::from_raw(self.raw().bbs) }
+            .map(|v| {
+                v.iter()
+                    .map(|bb| BasicBlock {
+                        fcn: *self,
+                        bb: NonNull::from(bb),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Local variables and arguments.
+    pub fn vars(&self) -> Vec<Variable> {
+        unsafe { PVectorRef::<RzAnalysisVar>::from_raw(self.raw().vars) }
+            .map(|v| v.iter().map(|var| unsafe { self.var(var) }).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn args(&self) -> Vec<Variable> {
+        self.vars().into_iter().filter(|v| v.is_arg).collect()
+    }
+
+    pub fn locals(&self) -> Vec<Variable> {
+        self.vars().into_iter().filter(|v| !v.is_arg).collect()
+    }
+
+    unsafe fn var(&self, var: &RzAnalysisVar) -> Variable {
+        let storage = match var.storage.type_ {
+            RzAnalysisVarStorageType_RZ_ANALYSIS_VAR_STORAGE_REG => VarStorage::Reg(
+                cstr_to_string(var.storage.__bindgen_anon_1.reg).unwrap_or_default(),
+            ),
+            RzAnalysisVarStorageType_RZ_ANALYSIS_VAR_STORAGE_STACK => {
+                VarStorage::Stack(var.storage.__bindgen_anon_1.stack_off)
+            }
+            _ => VarStorage::Other,
+        };
+        let type_name = if var.type_.is_null() {
+            None
+        } else {
+            let s = rz_type_as_string((*self.core.analysis()).typedb, var.type_);
+            let res = cstr_to_string(s);
+            free(s as _);
+            res
+        };
+        Variable {
+            name: cstr_to_string(var.name).unwrap_or_default(),
+            type_name,
+            storage,
+            is_arg: rz_analysis_var_is_arg(var as *const _ as _),
+        }
+    }
+}
+
+impl<'a> BasicBlock<'a> {
+    fn raw(&self) -> &'a RzAnalysisBlock {
+        unsafe { &*self.bb.as_ptr() }
+    }
+
+    pub fn function(&self) -> Function<'a> {
+        self.fcn
+    }
+
+    pub fn addr(&self) -> u64 {
+        self.raw().addr
+    }
+
+    pub fn size(&self) -> u64 {
+        self.raw().size
+    }
+
+    pub fn ninstr(&self) -> usize {
+        self.raw().ninstr as _
+    }
+
+    pub fn jump(&self) -> Option<u64> {
+        opt_addr(self.raw().jump)
+    }
+
+    /// The fall-through edge of a conditional branch.
+    pub fn fail(&self) -> Option<u64> {
+        opt_addr(self.raw().fail)
+    }
+
+    pub fn switch_cases(&self) -> Vec<SwitchCase> {
+        let Some(op) = (unsafe { self.raw().switch_op.as_ref() }) else {
+            return vec![];
+        };
+        unsafe { ListRef::<RzAnalysisCaseOp>::from_raw(op.cases) }
+            .map(|l| {
+                l.iter()
+                    .map(|c| SwitchCase {
+                        value: c.value,
+                        jump: c.jump,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Addresses of the jump, fail and switch targets.
+    pub fn successors(&self) -> Vec<u64> {
+        let mut res = self
+            .jump()
+            .into_iter()
+            .chain(self.fail())
+            .collect::<Vec<_>>();
+        for c in self.switch_cases() {
+            if !res.contains(&c.jump) {
+                res.push(c.jump);
+            }
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrapper::*;
+
+    #[test]
+    fn test_analyze_function() {
+        let mut core = Core::new();
+        core.set("analysis.arch", "x86").unwrap();
+        core.set("analysis.bits", "64").unwrap();
+        // test eax, eax; je 5; nop; ret
+        let code = [0x85, 0xc0, 0x74, 0x01, 0x90, 0xc3];
+        let io = core.io();
+        io.open("malloc://0x100", Perm::R | Perm::W | Perm::X)
+            .unwrap();
+        io.write_at(0, &code).unwrap();
+        drop(io);
+        core.analyze_function(0).unwrap();
+        let fcn = core.function_at(0).unwrap();
+        assert_eq!(fcn.addr(), 0);
+        assert_eq!(fcn.size(), code.len() as u64);
+        let mut blocks = fcn.blocks();
+        blocks.sort_by_key(|b| b.addr());
+        assert_eq!(
+            blocks.iter().map(|b| b.addr()).collect::<Vec<_>>(),
+            [0, 4, 5]
+        );
+        assert_eq!(blocks[0].jump(), Some(5));
+        assert_eq!(blocks[0].fail(), Some(4));
+        assert_eq!(blocks[0].successors(), [5, 4]);
+        assert_eq!(blocks[2].successors(), []);
+        assert!(core.functions().iter().any(|f| f.addr() == 0));
+    }
+}
@@ -0,0 +1,148 @@
+use std::ffi::CString;
+use std::fmt;
+
+use crate::wrapper::{AnalysisOp, Core, Result, RizinError};
+use crate::*;
+
+/// Where and why [`Core::assemble`] failed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AsmError {
+    /// 1-based line of the input.
+    pub line: usize,
+    /// 1-based column of the offending statement.
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for AsmError {}
+
+/// Labels defined in `text`, i.e. statements like `loop:`.
+fn labels(text: &str) -> Vec<&str> {
+    text.lines()
+        .filter_map(|l| l.trim().strip_suffix(':'))
+        .filter(|l| !l.is_empty() && !l.contains(char::is_whitespace))
+        .collect()
+}
+
+impl Core {
+    fn massemble(&self, text: &str, addr: u64) -> Option<Vec<u8>> {
+        let ctext = CString::new(text).ok()?;
+        unsafe {
+            let rasm = self.0.as_ref().rasm;
+            rz_asm_set_pc(rasm, addr);
+            let code = rz_asm_massemble(rasm, ctext.as_ptr());
+            let res = code.as_ref().and_then(|c| {
+                (c.len > 0 && !c.bytes.is_null())
+                    .then(|| std::slice::from_raw_parts(c.bytes, c.len as _).to_vec())
+            });
+            rz_asm_code_free(code);
+            res
+        }
+    }
+
+    /// Assembles `text`, one instruction or directive per line or separated
+    /// by `;`, as if placed at `addr`. Labels such as `loop:` may be used.
+    ///
+    /// On failure the error is a [`RizinError::Asm`] locating the statement.
+    pub fn assemble(&self, text: &str, addr: u64) -> Result<Vec<u8>> {
+        if let Some(bytes) = self.massemble(text, addr) {
+            return Ok(bytes);
+        }
+        Err(self.locate_asm_error(text, addr).into())
+    }
+
+    /// Finds the first statement that doesn't assemble on its own. Statements
+    /// using labels can't be checked in isolation and are skipped.
+    fn locate_asm_error(&self, text: &str, addr: u64) -> AsmError {
+        let labels = labels(text);
+        for (i, line) in text.lines().enumerate() {
+            let mut column = 1;
+            for stmt in line.split(';') {
+                let trimmed = stmt.trim();
+                let start = column + (stmt.len() - stmt.trim_start().len());
+                column += stmt.len() + 1;
+                if trimmed.is_empty()
+                    || trimmed.ends_with(':')
+                    || trimmed
+                        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+                        .any(|token| labels.contains(&token))
+                {
+                    continue;
+                }
+                if self.massemble(trimmed, addr).is_none() {
+                    return AsmError {
+                        line: i + 1,
+                        column: start,
+                        message: format!("failed assemble `{}`", trimmed),
+                    };
+                }
+            }
+        }
+        AsmError {
+            line: 1,
+            column: 1,
+            message: "failed assemble".to_owned(),
+        }
+    }
+
+    /// Assembles the disassembly of `op` at its address.
+    pub fn reassemble(&self, op: &AnalysisOp) -> Result<Vec<u8>> {
+        self.assemble(op.mnemonic()?, op.addr())
+    }
+
+    /// Checks that `op`, decoded from `bytes`, assembles back to the same bytes.
+    pub fn round_trips(&self, op: &AnalysisOp, bytes: &[u8]) -> Result<bool> {
+        let expected = bytes.get(..op.size()).ok_or(RizinError::InvalidArgument(
+            "op is larger than its bytes".to_owned(),
+        ))?;
+        Ok(self.reassemble(op)? == expected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrapper::*;
+
+    fn x86() -> Core {
+        let core = Core::new();
+        core.set("asm.arch", "x86").unwrap();
+        core.set("asm.bits", "64").unwrap();
+        core.set("analysis.arch", "x86").unwrap();
+        core.set("analysis.bits", "64").unwrap();
+        core
+    }
+
+    #[test]
+    fn test_assemble() {
+        let core = x86();
+        assert_eq!(core.assemble("nop; ret", 0).unwrap(), [0x90, 0xc3]);
+        assert_eq!(
+            core.assemble("loop:\nnop\njmp loop", 0x1000).unwrap(),
+            [0x90, 0xeb, 0xfd]
+        );
+        let RizinError::Asm(err) = core.assemble("nop\n  bogus rax", 0).unwrap_err() else {
+            panic!("expected an asm error");
+        };
+        assert_eq!((err.line, err.column), (2, 3));
+        // `a` is a substring of `bogus rax`, not a reference to the label.
+        let RizinError::Asm(err) = core.assemble("a:\nnop\nbogus rax", 0).unwrap_err() else {
+            panic!("expected an asm error");
+        };
+        assert_eq!((err.line, err.column), (3, 1));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let core = x86();
+        // push rbp
+        let bytes = [0x55];
+        let op = core.analysis_op(&bytes, 0).unwrap();
+        assert!(core.round_trips(&op, &bytes).unwrap());
+    }
+}
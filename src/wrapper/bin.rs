@@ -0,0 +1,423 @@
+use std::ffi::{c_char, CString};
+use std::marker::PhantomData;
+use std::path::Path;
+use std::ptr::NonNull;
+use std::slice;
+
+use bitflags::bitflags;
+
+use crate::wrapper::{cstr_to_string, Core, Demangler, PVector, PVectorRef, Result, RizinError};
+use crate::*;
+
+bitflags! {
+    /// Access permissions of a section or map (`RZ_PERM_*`).
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+    pub struct Perm: u32 {
+        const R = RZ_PERM_R;
+        const W = RZ_PERM_W;
+        const X = RZ_PERM_X;
+        const SHAR = RZ_PERM_SHAR;
+        const PRIV = RZ_PERM_PRIV;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    pub name: String,
+    pub vaddr: u64,
+    pub paddr: u64,
+    pub size: u64,
+    pub vsize: u64,
+    pub align: u64,
+    pub perm: Perm,
+    pub is_data: bool,
+    pub is_segment: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    /// Demangled name, set when the object's language has a demangler.
+    pub demangled: Option<String>,
+    pub libname: Option<String>,
+    pub bind: Option<String>,
+    pub kind: Option<String>,
+    pub vaddr: u64,
+    pub paddr: u64,
+    pub size: u64,
+    pub ordinal: u32,
+    pub is_imported: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Import {
+    pub name: String,
+    pub libname: Option<String>,
+    pub bind: Option<String>,
+    pub kind: Option<String>,
+    pub ordinal: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntryPoint {
+    pub vaddr: u64,
+    pub paddr: u64,
+    pub hvaddr: u64,
+    pub hpaddr: u64,
+    pub kind: i32,
+    pub bits: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reloc {
+    pub vaddr: u64,
+    pub paddr: u64,
+    pub target_vaddr: u64,
+    pub addend: i64,
+    pub additive: bool,
+    /// Name of the symbol or import the relocation refers to.
+    pub name: Option<String>,
+}
+
+impl Section {
+    unsafe fn from_raw(s: &RzBinSection) -> Self {
+        Self {
+            name: cstr_to_string(s.name).unwrap_or_default(),
+            vaddr: s.vaddr,
+            paddr: s.paddr,
+            size: s.size,
+            vsize: s.vsize,
+            align: s.align,
+            perm: Perm::from_bits_truncate(s.perm as _),
+            is_data: s.is_data,
+            is_segment: s.is_segment,
+        }
+    }
+}
+
+impl Symbol {
+    unsafe fn from_raw(s: &RzBinSymbol) -> Self {
+        Self {
+            name: cstr_to_string(s.name).unwrap_or_default(),
+            demangled: None,
+            libname: cstr_to_string(s.libname),
+            bind: cstr_to_string(s.bind),
+            kind: cstr_to_string(s.type_),
+            vaddr: s.vaddr,
+            paddr: s.paddr,
+            size: s.size as _,
+            ordinal: s.ordinal,
+            is_imported: s.is_imported,
+        }
+    }
+
+    /// The demangled name if there is one, else the raw name.
+    pub fn display_name(&self) -> &str {
+        self.demangled.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Mirrors `rz_core_sym_is_export`: global symbols that are not imported.
+    pub fn is_exported(&self) -> bool {
+        !self.is_imported && self.bind.as_deref() == Some("GLOBAL")
+    }
+}
+
+impl Import {
+    unsafe fn from_raw(i: &RzBinImport) -> Self {
+        Self {
+            name: cstr_to_string(i.name).unwrap_or_default(),
+            libname: cstr_to_string(i.libname),
+            bind: cstr_to_string(i.bind),
+            kind: cstr_to_string(i.type_),
+            ordinal: i.ordinal,
+        }
+    }
+}
+
+impl EntryPoint {
+    fn from_raw(a: &RzBinAddr) -> Self {
+        Self {
+            vaddr: a.vaddr,
+            paddr: a.paddr,
+            hvaddr: a.hvaddr,
+            hpaddr: a.hpaddr,
+            kind: a.type_ as _,
+            bits: a.bits as _,
+        }
+    }
+}
+
+impl Reloc {
+    unsafe fn from_raw(r: &RzBinReloc) -> Self {
+        let name = if let Some(sym) = r.symbol.as_ref() {
+            cstr_to_string(sym.name)
+        } else if let Some(imp) = r.import.as_ref() {
+            cstr_to_string(imp.name)
+        } else {
+            None
+        };
+        Self {
+            vaddr: r.vaddr,
+            paddr: r.paddr,
+            target_vaddr: r.target_vaddr,
+            addend: r.addend,
+            additive: r.additive,
+            name,
+        }
+    }
+}
+
+pub struct BinFile<'a> {
+    pub(crate) bin: NonNull<RzBin>,
+    pub bf: NonNull<RzBinFile>,
+    mem: Option<MemSource>,
+    marker: PhantomData<&'a RzBin>,
+}
+
+/// The `rzbuf://` IO descriptor backing a [`BinFile`] opened from memory.
+struct MemSource {
+    desc: NonNull<RzIODesc>,
+    buf: NonNull<RzBuffer>,
+}
+
+impl Drop for MemSource {
+    fn drop(&mut self) {
+        unsafe {
+            rz_io_desc_close(self.desc.as_ptr());
+            rz_buf_free(self.buf.as_ptr());
+        }
+    }
+}
+
+/// A standalone `RzBin` with its own `RzIO`, for loading binaries without a full `RzCore`.
+pub struct Bin {
+    pub bin: NonNull<RzBin>,
+    io: NonNull<RzIO>,
+}
+
+impl Bin {
+    pub fn new() -> Self {
+        unsafe {
+            let bin = NonNull::new(rz_bin_new()).unwrap();
+            let io = NonNull::new(rz_io_new()).unwrap();
+            rz_io_bind(io.as_ptr(), &mut (*bin.as_ptr()).iob);
+            Self { bin, io }
+        }
+    }
+
+    pub fn open<P: AsRef<Path>>(&mut self, path: P) -> Result<BinFile<'_>> {
+        unsafe { BinFile::open(self.bin, path.as_ref()) }
+    }
+
+    /// Loads a binary from memory. The bytes are copied into an `RzBuffer`
+    /// that is exposed to rizin through the `rzbuf://` IO plugin.
+    pub fn open_slice<B: AsRef<[u8]>>(&mut self, data: B) -> Result<BinFile<'_>> {
+        let data = data.as_ref();
+        unsafe {
+            let buf = NonNull::new(rz_buf_new_with_bytes(data.as_ptr(), data.len() as _))
+                .ok_or(RizinError::NullPointer { what: "buffer" })?;
+            let desc = rz_io_open_buffer(self.io.as_ptr(), buf.as_ptr(), RZ_PERM_R as _, 0);
+            let Some(desc) = NonNull::new(desc) else {
+                rz_buf_free(buf.as_ptr());
+                return Err(RizinError::OpenFailed {
+                    path: "buffer".to_owned(),
+                });
+            };
+            let mem = MemSource { desc, buf };
+
+            let mut rz_bin_opt = RzBinOptions::default();
+            rz_bin_options_init(&mut rz_bin_opt, desc.as_ref().fd, 0, 0, false);
+            rz_bin_opt.sz = data.len() as _;
+            let bf = rz_bin_open_io(self.bin.as_ptr(), &mut rz_bin_opt);
+            Ok(BinFile {
+                bin: self.bin,
+                bf: NonNull::new(bf).ok_or(RizinError::OpenFailed {
+                    path: "buffer".to_owned(),
+                })?,
+                mem: Some(mem),
+                marker: PhantomData,
+            })
+        }
+    }
+}
+
+impl Default for Bin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Bin {
+    fn drop(&mut self) {
+        unsafe {
+            rz_bin_free(self.bin.as_ptr());
+            rz_io_free(self.io.as_ptr());
+        }
+    }
+}
+
+impl Core {
+    pub fn open<P: AsRef<Path>>(&self, path: P) -> Result<BinFile<'_>> {
+        unsafe { BinFile::open(NonNull::new(self.0.as_ref().bin).unwrap(), path.as_ref()) }
+    }
+}
+
+impl BinFile<'_> {
+    unsafe fn open<'a>(bin: NonNull<RzBin>, path: &Path) -> Result<BinFile<'a>> {
+        let cpath = CString::new(path.to_str().ok_or(RizinError::Utf8)?)?;
+        let mut rz_bin_opt = RzBinOptions::default();
+        rz_bin_options_init(&mut rz_bin_opt, 0, 0, 0, false);
+        let bf = rz_bin_open(bin.as_ptr(), cpath.as_ptr(), &mut rz_bin_opt);
+        Ok(BinFile {
+            bin,
+            bf: NonNull::new(bf).ok_or(RizinError::OpenFailed {
+                path: path.display().to_string(),
+            })?,
+            mem: None,
+            marker: PhantomData,
+        })
+    }
+
+    pub fn object(&self) -> Result<BinObject<'_>> {
+        let o = unsafe { self.bf.as_ref().o };
+        NonNull::new(o)
+            .map(|obj| BinObject {
+                obj,
+                marker: PhantomData,
+            })
+            .ok_or(RizinError::NullPointer { what: "bin object" })
+    }
+}
+
+impl Drop for BinFile<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            rz_bin_file_delete(self.bin.as_ptr(), self.bf.as_ptr());
+        }
+        // The buffer has to outlive the bin file that reads from it.
+        drop(self.mem.take());
+    }
+}
+
+/// The loaded object of a [`BinFile`]; everything it yields is copied out.
+pub struct BinObject<'a> {
+    pub(crate) obj: NonNull<RzBinObject>,
+    marker: PhantomData<&'a RzBinFile>,
+}
+
+impl BinObject<'_> {
+    pub fn sections(&self) -> Vec<Section> {
+        self.collect_sections(false)
+    }
+
+    pub fn segments(&self) -> Vec<Section> {
+        self.collect_sections(true)
+    }
+
+    fn collect_sections(&self, segments: bool) -> Vec<Section> {
+        let v = unsafe {
+            if segments {
+                rz_bin_object_get_segments(self.obj.as_ptr())
+            } else {
+                rz_bin_object_get_sections(self.obj.as_ptr())
+            }
+        };
+        PVector::<RzBinSection>::try_from(v)
+            .map(|v| v.iter().map(|s| unsafe { Section::from_raw(s) }).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn symbols(&self) -> Vec<Symbol> {
+        let mut res: Vec<Symbol> = unsafe {
+            // The vectors below are owned by the bin object.
+            PVectorRef::<RzBinSymbol>::from_raw(rz_bin_object_get_symbols(self.obj.as_ptr()))
+                .map(|v| v.iter().map(|s| Symbol::from_raw(s)).collect())
+                .unwrap_or_default()
+        };
+        if let Some(lang) = self.language() {
+            let demangler = Demangler::new();
+            for s in &mut res {
+                s.demangled = demangler.demangle(lang, &s.name);
+            }
+        }
+        res
+    }
+
+    pub fn imports(&self) -> Vec<Import> {
+        unsafe {
+            PVectorRef::<RzBinImport>::from_raw(rz_bin_object_get_imports(self.obj.as_ptr()))
+                .map(|v| v.iter().map(|i| Import::from_raw(i)).collect())
+                .unwrap_or_default()
+        }
+    }
+
+    pub fn exports(&self) -> Vec<Symbol> {
+        self.symbols()
+            .into_iter()
+            .filter(Symbol::is_exported)
+            .collect()
+    }
+
+    pub fn entrypoints(&self) -> Vec<EntryPoint> {
+        unsafe {
+            PVectorRef::<RzBinAddr>::from_raw(rz_bin_object_get_entries(self.obj.as_ptr()))
+                .map(|v| v.iter().map(|a| EntryPoint::from_raw(a)).collect())
+                .unwrap_or_default()
+        }
+    }
+
+    pub fn libraries(&self) -> Vec<String> {
+        unsafe {
+            PVectorRef::<c_char>::from_raw(rz_bin_object_get_libs(self.obj.as_ptr()))
+                .map(|v| v.iter().filter_map(|l| cstr_to_string(l)).collect())
+                .unwrap_or_default()
+        }
+    }
+
+    pub fn relocations(&self) -> Vec<Reloc> {
+        unsafe {
+            match rz_bin_object_get_relocs(self.obj.as_ptr()).as_ref() {
+                Some(storage) if !storage.relocs.is_null() => {
+                    slice::from_raw_parts(storage.relocs, storage.relocs_count)
+                        .iter()
+                        .map(|r| Reloc::from_raw(&**r))
+                        .collect()
+                }
+                _ => vec![],
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::wrapper::*;
+
+    #[test]
+    fn test_bin_object() {
+        let path = Path::new("/bin/ls");
+        if !path.exists() {
+            return;
+        }
+        let core = Core::new();
+        let bf = core.open(path).unwrap();
+        let obj = bf.object().unwrap();
+        assert!(!obj.sections().is_empty());
+        assert!(!obj.entrypoints().is_empty());
+        assert!(obj.imports().iter().all(|i| !i.name.is_empty()));
+    }
+
+    #[test]
+    fn test_open_slice() {
+        let Ok(data) = std::fs::read("/bin/ls") else {
+            return;
+        };
+        let mut bin = Bin::new();
+        let bf = bin.open_slice(&data).unwrap();
+        assert!(!bf.object().unwrap().sections().is_empty());
+        drop(bf);
+        assert!(bin.open_slice([0u8; 0]).is_err());
+    }
+}
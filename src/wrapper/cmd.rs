@@ -0,0 +1,182 @@
+use std::ffi::CString;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Mutex, PoisonError};
+
+#[cfg(feature = "serde")]
+use serde::de::DeserializeOwned;
+
+use crate::wrapper::{cstr_to_string, Core, Result, RizinError};
+use crate::*;
+
+/// Outcome of a rizin shell command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CmdStatus {
+    Ok,
+    WrongArgs,
+    Error,
+    Invalid,
+    NonExistingCmd,
+    Exit,
+}
+
+impl From<RzCmdStatus> for CmdStatus {
+    fn from(value: RzCmdStatus) -> Self {
+        match value {
+            RzCmdStatus_RZ_CMD_STATUS_OK => CmdStatus::Ok,
+            RzCmdStatus_RZ_CMD_STATUS_WRONG_ARGS => CmdStatus::WrongArgs,
+            RzCmdStatus_RZ_CMD_STATUS_INVALID => CmdStatus::Invalid,
+            RzCmdStatus_RZ_CMD_STATUS_NONEXISTINGCMD => CmdStatus::NonExistingCmd,
+            RzCmdStatus_RZ_CMD_STATUS_EXIT => CmdStatus::Exit,
+            _ => CmdStatus::Error,
+        }
+    }
+}
+
+/// Guards the rizin console, which is shared by every core of the process.
+static CONS: Mutex<()> = Mutex::new(());
+
+impl Core {
+    /// Runs `cmd` like the rizin shell and returns what it printed.
+    ///
+    /// Takes `&mut self` since commands can rewrite the analysis, see
+    /// [`Function`](crate::wrapper::Function). A failing command returns
+    /// [`RizinError::Cmd`] with its status. [`CmdStatus::Exit`], returned by
+    /// quit commands such as `q`, counts as success as the core stays usable.
+    pub fn cmd(&mut self, cmd: &str) -> Result<String> {
+        let ccmd = CString::new(cmd)?;
+        let _cons = CONS.lock().unwrap_or_else(PoisonError::into_inner);
+        let (status, out) = unsafe {
+            rz_cons_push();
+            let status = rz_core_cmd_rzshell(self.0.as_ptr(), ccmd.as_ptr(), 0);
+            rz_cons_filter();
+            let out = cstr_to_string(rz_cons_get_buffer());
+            rz_cons_pop();
+            rz_cons_echo(std::ptr::null());
+            (CmdStatus::from(status), out.unwrap_or_default())
+        };
+        match status {
+            CmdStatus::Ok | CmdStatus::Exit => Ok(out),
+            status => Err(RizinError::Cmd {
+                cmd: cmd.to_owned(),
+                status,
+            }),
+        }
+    }
+
+    /// Runs a JSON printing command such as `ij` and deserializes its output.
+    #[cfg(feature = "serde")]
+    pub fn cmd_json<T: DeserializeOwned>(&mut self, cmd: &str) -> Result<T> {
+        let out = self.cmd(cmd)?;
+        Ok(serde_json::from_str(&out)?)
+    }
+
+    /// The current seek.
+    pub fn offset(&self) -> u64 {
+        unsafe { self.0.as_ref().offset }
+    }
+
+    pub fn block_size(&self) -> u32 {
+        unsafe { self.0.as_ref().blocksize }
+    }
+
+    /// Seeks to `addr` until the returned guard is dropped.
+    ///
+    /// The guard derefs to the core, so `core.at(addr).cmd("pd 1")` runs a
+    /// single command at `addr`.
+    pub fn at(&mut self, addr: u64) -> SeekGuard<'_> {
+        let (offset, blocksize) = (self.offset(), self.block_size());
+        unsafe {
+            rz_core_seek(self.0.as_ptr(), addr, true);
+        }
+        SeekGuard {
+            core: self,
+            offset,
+            blocksize,
+        }
+    }
+}
+
+/// Restores the seek and block size of a [`Core`] on drop, see [`Core::at`].
+pub struct SeekGuard<'a> {
+    core: &'a mut Core,
+    offset: u64,
+    blocksize: u32,
+}
+
+impl SeekGuard<'_> {
+    /// Also overrides the block size while the guard lives.
+    pub fn with_block_size(self, size: u32) -> Result<Self> {
+        if unsafe { rz_core_block_size(self.core.0.as_ptr(), size as _) } {
+            Ok(self)
+        } else {
+            Err(RizinError::InvalidArgument(format!(
+                "failed set block size {}",
+                size
+            )))
+        }
+    }
+}
+
+impl Deref for SeekGuard<'_> {
+    type Target = Core;
+
+    fn deref(&self) -> &Self::Target {
+        self.core
+    }
+}
+
+impl DerefMut for SeekGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.core
+    }
+}
+
+impl Drop for SeekGuard<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            let core = self.core.0.as_ptr();
+            rz_core_block_size(core, self.blocksize as _);
+            rz_core_seek(core, self.offset, true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrapper::*;
+
+    #[test]
+    fn test_cmd() {
+        let mut core = Core::new();
+        assert_eq!(core.cmd("?e hello").unwrap(), "hello\n");
+        assert!(matches!(
+            core.cmd("not-a-command"),
+            Err(RizinError::Cmd {
+                status: CmdStatus::NonExistingCmd,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_cmd_at() {
+        let mut core = Core::new();
+        let blocksize = core.block_size();
+        {
+            let mut at = core.at(0x100).with_block_size(0x20).unwrap();
+            assert_eq!(at.offset(), 0x100);
+            assert_eq!(at.block_size(), 0x20);
+            assert_eq!(at.cmd("s").unwrap().trim(), "0x100");
+        }
+        assert_eq!(core.offset(), 0);
+        assert_eq!(core.block_size(), blocksize);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_cmd_json() {
+        let mut core = Core::new();
+        let plugins: serde_json::Value = core.cmd_json("Laj").unwrap();
+        assert!(plugins.is_array());
+    }
+}
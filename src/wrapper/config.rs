@@ -0,0 +1,216 @@
+use std::ffi::{c_char, CString};
+use std::ptr::NonNull;
+
+use crate::wrapper::{cstr_to_string, Core, ListRef, Result, RizinError};
+use crate::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConfigType {
+    Bool,
+    Int,
+    Offset,
+    Str,
+}
+
+/// A snapshot of an `RzConfigNode`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigNode {
+    pub name: String,
+    pub value: String,
+    pub desc: Option<String>,
+    pub kind: ConfigType,
+    pub read_only: bool,
+    /// The values rizin suggests. The list is advisory: some keys accept
+    /// others, and lists such as the cpus of `asm.cpu` follow the arch.
+    pub options: Vec<String>,
+}
+
+impl ConfigNode {
+    unsafe fn from_raw(node: &RzConfigNode) -> Self {
+        let flags = node.flags as u32;
+        let kind = if flags & CN_BOOL != 0 {
+            ConfigType::Bool
+        } else if flags & CN_OFFT != 0 {
+            ConfigType::Offset
+        } else if flags & CN_INT != 0 {
+            ConfigType::Int
+        } else {
+            ConfigType::Str
+        };
+        let options = ListRef::<c_char>::from_raw(node.options)
+            .map(|l| l.iter().filter_map(|o| cstr_to_string(o)).collect())
+            .unwrap_or_default();
+        Self {
+            name: cstr_to_string(node.name).unwrap_or_default(),
+            value: cstr_to_string(node.value).unwrap_or_default(),
+            desc: cstr_to_string(node.desc),
+            kind,
+            read_only: flags & CN_RO != 0,
+            options,
+        }
+    }
+
+    /// Checks `value` against the node type before it reaches the setter,
+    /// which has the final say on other values.
+    pub fn validate(&self, value: &str) -> Result<()> {
+        if self.read_only {
+            return Err(RizinError::ReadOnly {
+                key: self.name.clone(),
+            });
+        }
+        // The same spellings the setter accepts, such as "on" or "no".
+        let valid = self.kind != ConfigType::Bool
+            || CString::new(value)
+                .map(|v| unsafe { rz_str_is_true(v.as_ptr()) || rz_str_is_false(v.as_ptr()) })
+                .unwrap_or(false);
+        if valid {
+            Ok(())
+        } else {
+            Err(RizinError::ConfigKey {
+                key: self.name.clone(),
+                value: Some(value.to_owned()),
+                options: vec!["true".to_owned(), "false".to_owned()],
+            })
+        }
+    }
+
+    /// The error for a `value` the setter refused, listing the suggested options.
+    fn rejected(&self, value: impl ToString) -> RizinError {
+        RizinError::ConfigKey {
+            key: self.name.clone(),
+            value: Some(value.to_string()),
+            options: self.options.clone(),
+        }
+    }
+}
+
+impl Core {
+    fn config(&self) -> *mut RzConfig {
+        unsafe { self.0.as_ref().config }
+    }
+
+    fn config_node_ptr(&self, k: &str) -> Result<NonNull<RzConfigNode>> {
+        let ck = CString::new(k)?;
+        let node = unsafe { rz_config_node_get(self.config(), ck.as_ptr()) };
+        NonNull::new(node).ok_or(RizinError::ConfigKey {
+            key: k.to_owned(),
+            value: None,
+            options: vec![],
+        })
+    }
+
+    pub fn config_node(&self, k: &str) -> Result<ConfigNode> {
+        let node = self.config_node_ptr(k)?;
+        Ok(unsafe { ConfigNode::from_raw(node.as_ref()) })
+    }
+
+    pub fn config_nodes(&self) -> Vec<ConfigNode> {
+        let nodes = unsafe { (*self.config()).nodes };
+        // The nodes belong to the config.
+        unsafe { ListRef::<RzConfigNode>::from_raw(nodes) }
+            .map(|l| {
+                l.iter()
+                    .map(|n| unsafe { ConfigNode::from_raw(n) })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn get(&self, k: &str) -> Result<String> {
+        let node = self.config_node_ptr(k)?;
+        let ck = CString::new(k)?;
+        let v = unsafe { rz_config_get(self.config(), ck.as_ptr()) };
+        unsafe { cstr_to_string(v) }
+            .or_else(|| unsafe { cstr_to_string(node.as_ref().value) })
+            .ok_or(RizinError::NullPointer {
+                what: "config value",
+            })
+    }
+
+    pub fn get_i(&self, k: &str) -> Result<u64> {
+        self.config_node_ptr(k)?;
+        let ck = CString::new(k)?;
+        Ok(unsafe { rz_config_get_i(self.config(), ck.as_ptr()) })
+    }
+
+    pub fn get_b(&self, k: &str) -> Result<bool> {
+        self.config_node_ptr(k)?;
+        let ck = CString::new(k)?;
+        Ok(unsafe { rz_config_get_b(self.config(), ck.as_ptr()) })
+    }
+
+    pub fn set(&self, k: &str, v: &str) -> Result<&Self> {
+        let n = self.config_node(k)?;
+        n.validate(v)?;
+        let node = unsafe {
+            rz_config_set(
+                self.config(),
+                CString::new(k)?.as_ptr(),
+                CString::new(v)?.as_ptr(),
+            )
+        };
+        NonNull::new(node)
+            .map(|_| self)
+            .ok_or_else(|| n.rejected(v))
+    }
+
+    pub fn set_i(&self, k: &str, v: u64) -> Result<&Self> {
+        let n = self.config_node(k)?;
+        if n.read_only {
+            return Err(RizinError::ReadOnly { key: k.to_owned() });
+        }
+        let node = unsafe { rz_config_set_i(self.config(), CString::new(k)?.as_ptr(), v) };
+        NonNull::new(node)
+            .map(|_| self)
+            .ok_or_else(|| n.rejected(v))
+    }
+
+    pub fn set_b(&self, k: &str, v: bool) -> Result<&Self> {
+        let n = self.config_node(k)?;
+        if n.kind != ConfigType::Bool {
+            return Err(n.rejected(v));
+        }
+        n.validate(if v { "true" } else { "false" })?;
+        let node = unsafe { rz_config_set_b(self.config(), CString::new(k)?.as_ptr(), v) };
+        NonNull::new(node)
+            .map(|_| self)
+            .ok_or_else(|| n.rejected(v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrapper::*;
+
+    #[test]
+    fn test_config() {
+        let core = Core::new();
+        core.set_b("asm.bytes", false).unwrap();
+        assert!(!core.get_b("asm.bytes").unwrap());
+        core.set_i("asm.bits", 32).unwrap();
+        assert_eq!(core.get_i("asm.bits").unwrap(), 32);
+        core.set("analysis.arch", "x86").unwrap();
+        assert_eq!(core.get("analysis.arch").unwrap(), "x86");
+        assert!(core.config_nodes().iter().any(|n| n.name == "asm.bits"));
+    }
+
+    #[test]
+    fn test_config_invalid() {
+        let core = Core::new();
+        let err = core.set("analysis.arch", "tricroe").unwrap_err();
+        assert!(err.to_string().contains("tricore"));
+        assert!(matches!(
+            core.set("no.such.key", "1"),
+            Err(RizinError::ConfigKey { value: None, .. })
+        ));
+        assert!(core.set("asm.bytes", "maybe").is_err());
+        // Cpus are listed for the current arch only.
+        core.set("asm.cpu", "pic18").unwrap();
+        core.set("asm.arch", "pic").unwrap();
+        assert_eq!(core.get("asm.cpu").unwrap(), "pic18");
+        core.set("asm.bytes", "on").unwrap();
+        assert!(core.get_b("asm.bytes").unwrap());
+        core.set("asm.bytes", "no").unwrap();
+        assert!(!core.get_b("asm.bytes").unwrap());
+    }
+}
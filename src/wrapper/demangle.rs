@@ -0,0 +1,122 @@
+use std::ffi::CString;
+use std::ptr::NonNull;
+
+use crate::wrapper::{cstr_to_string, BinObject};
+use crate::*;
+
+/// Mangling schemes understood by rizin's demangler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DemangleLang {
+    Cxx,
+    Rust,
+    Swift,
+    ObjC,
+    Msvc,
+    Java,
+    Pascal,
+}
+
+impl DemangleLang {
+    /// Name of the demangler plugin.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cxx => "c++",
+            Self::Rust => "rust",
+            Self::Swift => "swift",
+            Self::ObjC => "objc",
+            Self::Msvc => "msvc",
+            Self::Java => "java",
+            Self::Pascal => "pascal",
+        }
+    }
+
+    /// Maps a language as detected by `rz_bin_language_to_string`.
+    pub fn from_bin_language(lang: &str) -> Option<Self> {
+        let res = match lang.trim_end_matches(" with blocks") {
+            "c++" | "cxx" | "cpp" => Self::Cxx,
+            "rust" => Self::Rust,
+            "swift" => Self::Swift,
+            "objc" | "objective-c" => Self::ObjC,
+            "msvc" => Self::Msvc,
+            "java" | "kotlin" | "groovy" | "dart" => Self::Java,
+            "pascal" | "freepascal" => Self::Pascal,
+            _ => return None,
+        };
+        Some(res)
+    }
+}
+
+/// An `RzDemangler`, reusable across many symbols.
+pub struct Demangler(NonNull<RzDemangler>);
+
+impl Demangler {
+    pub fn new() -> Self {
+        Self(NonNull::new(unsafe { rz_demangler_new() }).unwrap())
+    }
+
+    /// The demangled `symbol`, or `None` if it isn't mangled with `lang`.
+    pub fn demangle(&self, lang: DemangleLang, symbol: &str) -> Option<String> {
+        let csym = CString::new(symbol).ok()?;
+        let clang = CString::new(lang.name()).ok()?;
+        unsafe {
+            let s = rz_demangler_resolve(
+                self.0.as_ptr(),
+                csym.as_ptr(),
+                clang.as_ptr(),
+                RzDemanglerFlag_RZ_DEMANGLER_FLAG_ENABLE_ALL,
+            );
+            let res = cstr_to_string(s);
+            free(s as _);
+            res
+        }
+    }
+}
+
+impl Default for Demangler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Demangler {
+    fn drop(&mut self) {
+        unsafe {
+            rz_demangler_free(self.0.as_ptr());
+        }
+    }
+}
+
+/// Demangles a single symbol, see [`Demangler`] for many.
+pub fn demangle(lang: DemangleLang, symbol: &str) -> Option<String> {
+    Demangler::new().demangle(lang, symbol)
+}
+
+impl BinObject<'_> {
+    /// The source language detected from the binary, if it has a demangler.
+    pub fn language(&self) -> Option<DemangleLang> {
+        let lang = unsafe { cstr_to_string(rz_bin_language_to_string(self.obj.as_ref().lang)) }?;
+        DemangleLang::from_bin_language(&lang)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrapper::*;
+
+    #[test]
+    fn test_demangle() {
+        assert_eq!(
+            demangle(DemangleLang::Cxx, "_ZN3foo3barEv").as_deref(),
+            Some("foo::bar()")
+        );
+        assert_eq!(
+            demangle(
+                DemangleLang::Rust,
+                "_ZN4core3ptr13drop_in_place17h0123456789abcdefE"
+            )
+            .as_deref(),
+            Some("core::ptr::drop_in_place")
+        );
+        assert_eq!(demangle(DemangleLang::Cxx, "main"), None);
+    }
+}
@@ -0,0 +1,610 @@
+use std::ffi::{c_char, CStr};
+use std::marker::PhantomData;
+use std::mem::size_of;
+use std::ptr::{addr_of, null_mut, NonNull};
+use std::slice;
+
+use crate::wrapper::{
+    cstr_to_string, vector_slice, BinFile, ListRef, PVectorRef, Result, RizinError,
+};
+use crate::*;
+
+impl RzBinEndianReader {
+    fn new(input: &[u8], big_endian: bool) -> Self {
+        Self {
+            data: input.as_ptr() as _,
+            owned: false,
+            length: input.len() as _,
+            offset: 0,
+            big_endian,
+            relocations: null_mut(),
+        }
+    }
+}
+
+/// Heap-allocates a reader over `input` for the `rz_bin_dwarf_*_new` functions, which take ownership of it.
+/// The data itself is borrowed, so `input` must outlive whatever is parsed from it.
+unsafe fn reader(input: &[u8], big_endian: bool) -> *mut RzBinEndianReader {
+    let R = RzBinEndianReader::new(input, big_endian);
+    let ptr = libc::malloc(size_of::<RzBinEndianReader>());
+    libc::memcpy(ptr, addr_of!(R) as _, size_of::<RzBinEndianReader>());
+    ptr as _
+}
+
+/// Parsed `.debug_abbrev`, borrowing the section bytes it was parsed from.
+pub struct DwarfAbbrev<'a> {
+    pub inner: NonNull<RzBinDwarfAbbrev>,
+    marker: PhantomData<&'a [u8]>,
+}
+
+impl<'a> DwarfAbbrev<'a> {
+    pub fn new(input: &'a [u8]) -> Result<Self> {
+        let abbrev = unsafe { rz_bin_dwarf_abbrev_new(reader(input, false)) };
+        NonNull::new(abbrev)
+            .map(|inner| Self {
+                inner,
+                marker: PhantomData,
+            })
+            .ok_or(RizinError::Parse {
+                what: ".debug_abbrev".to_owned(),
+            })
+    }
+
+    pub fn count(&self) -> usize {
+        unsafe { rz_bin_dwarf_abbrev_count(self.inner.as_ptr()) as _ }
+    }
+}
+
+impl Drop for DwarfAbbrev<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            rz_bin_dwarf_abbrev_free(self.inner.as_ptr());
+        }
+    }
+}
+
+/// The encoding used to decode `.debug_line` when no `.debug_info` is available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Encoding {
+    pub version: u16,
+    pub address_size: u8,
+    pub is_64bit: bool,
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Self {
+            version: 4,
+            address_size: 8,
+            is_64bit: false,
+        }
+    }
+}
+
+/// Raw `.debug_*` section contents for [`Dwarf::from_sections`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DwarfSections<'a> {
+    pub abbrev: Option<&'a [u8]>,
+    pub info: Option<&'a [u8]>,
+    pub line: Option<&'a [u8]>,
+    pub aranges: Option<&'a [u8]>,
+    pub str: Option<&'a [u8]>,
+    pub line_str: Option<&'a [u8]>,
+    pub str_offsets: Option<&'a [u8]>,
+    pub addr: Option<&'a [u8]>,
+    /// `.debug_loclists`, DWARF 5 location lists.
+    pub loclists: Option<&'a [u8]>,
+    /// `.debug_loc`, location lists before DWARF 5.
+    pub loc: Option<&'a [u8]>,
+    /// `.debug_rnglists`, DWARF 5 range lists.
+    pub rnglists: Option<&'a [u8]>,
+    /// `.debug_ranges`, range lists before DWARF 5.
+    pub ranges: Option<&'a [u8]>,
+    pub encoding: Option<Encoding>,
+}
+
+/// Parsed DWARF of a binary, borrowing from the [`BinFile`] or section bytes it was loaded from.
+pub struct Dwarf<'a> {
+    pub dw: NonNull<RzBinDWARF>,
+    marker: PhantomData<&'a [u8]>,
+}
+
+impl Drop for Dwarf<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            rz_bin_dwarf_free(self.dw.as_ptr());
+        }
+    }
+}
+
+impl BinFile<'_> {
+    pub fn dw(&mut self) -> Result<Dwarf<'_>> {
+        let dw = unsafe { rz_bin_dwarf_from_file(self.bf.as_ptr()) };
+        NonNull::new(dw)
+            .map(|dw| Dwarf {
+                dw,
+                marker: PhantomData,
+            })
+            .ok_or(RizinError::NotFound {
+                kind: "section",
+                name: "dwarf".to_owned(),
+            })
+    }
+}
+
+impl<'a> Dwarf<'a> {
+    pub fn from_sections(sections: DwarfSections<'a>, big_endian: bool) -> Result<Self> {
+        let dw = unsafe { libc::calloc(1, size_of::<RzBinDWARF>()) as *mut RzBinDWARF };
+        // Dropping `this` on an early return releases whatever was parsed so far.
+        let this = Self {
+            dw: NonNull::new(dw).ok_or(RizinError::NullPointer { what: "dwarf" })?,
+            marker: PhantomData,
+        };
+        let opt_reader = |x: Option<&[u8]>| match x {
+            Some(x) => unsafe { reader(x, big_endian) },
+            None => null_mut(),
+        };
+        unsafe {
+            let dw = &mut *dw;
+            if let Some(x) = sections.abbrev {
+                dw.abbrev = rz_bin_dwarf_abbrev_new(reader(x, big_endian));
+            }
+            if let Some(x) = sections.str {
+                dw.str = rz_bin_dwarf_str_new(reader(x, big_endian));
+            }
+            if let Some(x) = sections.line_str {
+                dw.line_str = rz_bin_dwarf_line_str_new(reader(x, big_endian));
+            }
+            // Units index these while parsing `.debug_info`.
+            if let Some(x) = sections.str_offsets {
+                dw.str_offsets = rz_bin_dwarf_str_offsets_new(reader(x, big_endian));
+            }
+            if let Some(x) = sections.addr {
+                dw.addr = rz_bin_dwarf_addr_new(reader(x, big_endian));
+            }
+            if let Some(x) = sections.info {
+                if dw.abbrev.is_null() {
+                    return Err(RizinError::InvalidArgument(
+                        ".debug_info requires .debug_abbrev".to_owned(),
+                    ));
+                }
+                dw.info = rz_bin_dwarf_info_from_buf(reader(x, big_endian), dw);
+                if dw.info.is_null() {
+                    return Err(RizinError::Parse {
+                        what: ".debug_info".to_owned(),
+                    });
+                }
+            }
+            if let Some(x) = sections.line {
+                let mut encoding = match (sections.encoding, this.units().next()) {
+                    (Some(e), _) => RzBinDWARFEncoding {
+                        version: e.version,
+                        address_size: e.address_size,
+                        is_64bit: e.is_64bit,
+                        big_endian,
+                    },
+                    (None, Some(unit)) => unit.0.hdr.encoding,
+                    (None, None) => {
+                        let e = Encoding::default();
+                        RzBinDWARFEncoding {
+                            version: e.version,
+                            address_size: e.address_size,
+                            is_64bit: e.is_64bit,
+                            big_endian,
+                        }
+                    }
+                };
+                dw.line = rz_bin_dwarf_line_new(
+                    reader(x, big_endian),
+                    &mut encoding,
+                    dw,
+                    RzBinDwarfLineInfoMask_RZ_BIN_DWARF_LINE_INFO_MASK_LINES_ALL,
+                );
+                if dw.line.is_null() {
+                    return Err(RizinError::Parse {
+                        what: ".debug_line".to_owned(),
+                    });
+                }
+            }
+            if let Some(x) = sections.aranges {
+                dw.aranges = rz_bin_dwarf_aranges_new(reader(x, big_endian));
+                if dw.aranges.is_null() {
+                    return Err(RizinError::Parse {
+                        what: ".debug_aranges".to_owned(),
+                    });
+                }
+            }
+            if sections.loclists.is_some() || sections.loc.is_some() {
+                dw.loclists = rz_bin_dwarf_loclists_new(
+                    opt_reader(sections.loclists),
+                    opt_reader(sections.loc),
+                );
+                if dw.loclists.is_null() {
+                    return Err(RizinError::Parse {
+                        what: ".debug_loclists".to_owned(),
+                    });
+                }
+            }
+            if sections.rnglists.is_some() || sections.ranges.is_some() {
+                dw.rnglists = rz_bin_dwarf_rnglists_new(
+                    opt_reader(sections.rnglists),
+                    opt_reader(sections.ranges),
+                );
+                if dw.rnglists.is_null() {
+                    return Err(RizinError::Parse {
+                        what: ".debug_rnglists".to_owned(),
+                    });
+                }
+            }
+        }
+        Ok(this)
+    }
+
+    fn raw(&self) -> &RzBinDWARF {
+        unsafe { self.dw.as_ref() }
+    }
+
+    pub fn units(&self) -> impl Iterator<Item = CompUnit<'_>> {
+        let units = match unsafe { self.raw().info.as_ref() } {
+            Some(info) => unsafe { vector_slice::<RzBinDwarfCompUnit>(&info.units) },
+            None => &[],
+        };
+        units.iter().map(CompUnit)
+    }
+
+    /// Resolves a string attribute, following `.debug_str`/`.debug_str_offsets` references.
+    pub fn attr_string(&self, unit: &CompUnit, attr: &Attr) -> Option<String> {
+        unsafe {
+            cstr_to_string(rz_bin_dwarf_attr_string(
+                attr.0,
+                self.dw.as_ptr(),
+                unit.0.str_offsets_base,
+            ))
+        }
+    }
+
+    /// Rows of the line-number program, sorted by address.
+    pub fn lines(&self) -> Vec<LineRow> {
+        unsafe {
+            let Some(lines) = self.raw().line.as_ref().and_then(|l| l.lines.as_ref()) else {
+                return vec![];
+            };
+            if lines.samples.is_null() {
+                return vec![];
+            }
+            slice::from_raw_parts(lines.samples, lines.samples_count as _)
+                .iter()
+                .map(|s| LineRow {
+                    address: s.address,
+                    file: cstr_to_string(s.file),
+                    line: s.line,
+                    column: s.column,
+                })
+                .collect()
+        }
+    }
+
+    pub fn aranges(&self) -> Vec<ArangeSet> {
+        let Some(aranges) = (unsafe { self.raw().aranges.as_ref() }) else {
+            return vec![];
+        };
+        // The list belongs to the aranges.
+        let Some(list) = (unsafe { ListRef::<RzBinDwarfARangeSet>::from_raw(aranges.list) }) else {
+            return vec![];
+        };
+        list.iter()
+            .map(|set| ArangeSet {
+                debug_info_offset: set.debug_info_offset,
+                address_size: set.address_size,
+                ranges: if set.aranges.is_null() {
+                    vec![]
+                } else {
+                    unsafe { slice::from_raw_parts(set.aranges, set.aranges_count as _) }
+                        .iter()
+                        .map(|r| Range {
+                            begin: r.addr,
+                            end: r.addr.wrapping_add(r.length),
+                        })
+                        .collect()
+                },
+            })
+            .collect()
+    }
+
+    /// Location list at `offset` in `.debug_loc`/`.debug_loclists`, as referenced from `unit`.
+    pub fn loclist(&self, unit: &CompUnit, offset: u64) -> Option<Vec<LocListEntry>> {
+        unsafe {
+            let loclists = self.raw().loclists;
+            if loclists.is_null() {
+                return None;
+            }
+            let list = rz_bin_dwarf_loclists_get(
+                loclists,
+                self.raw().addr,
+                unit.0 as *const _ as _,
+                offset,
+            )
+            .as_ref()?;
+            let entries = PVectorRef::<RzBinDwarfLocListEntry>::from_raw(addr_of!(list.entries))?;
+            Some(
+                entries
+                    .iter()
+                    .map(|e| LocListEntry {
+                        range: Range {
+                            begin: e.range.begin,
+                            end: e.range.end,
+                        },
+                        expression: block_bytes(&e.expression).to_vec(),
+                    })
+                    .collect(),
+            )
+        }
+    }
+
+    /// Range list at `offset` in `.debug_ranges`/`.debug_rnglists`, as referenced from `unit`.
+    pub fn rnglist(&self, unit: &CompUnit, offset: u64) -> Option<Vec<Range>> {
+        unsafe {
+            let rnglists = self.raw().rnglists;
+            if rnglists.is_null() {
+                return None;
+            }
+            let list = rz_bin_dwarf_rnglists_get(
+                rnglists,
+                self.raw().addr,
+                unit.0 as *const _ as _,
+                offset,
+            )
+            .as_ref()?;
+            let entries = PVectorRef::<RzBinDwarfRange>::from_raw(addr_of!(list.entries))?;
+            Some(
+                entries
+                    .iter()
+                    .map(|r| Range {
+                        begin: r.begin,
+                        end: r.end,
+                    })
+                    .collect(),
+            )
+        }
+    }
+}
+
+unsafe fn block_bytes(block: &RzBinDwarfBlock) -> &[u8] {
+    if block.data.is_null() {
+        &[]
+    } else {
+        slice::from_raw_parts(block.data, block.length as _)
+    }
+}
+
+unsafe fn static_str(ptr: *const c_char) -> Option<&'static str> {
+    if ptr.is_null() {
+        None
+    } else {
+        CStr::from_ptr(ptr).to_str().ok()
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct CompUnit<'a>(pub &'a RzBinDwarfCompUnit);
+
+impl<'a> CompUnit<'a> {
+    pub fn offset(&self) -> u64 {
+        self.0.offset
+    }
+
+    pub fn version(&self) -> u16 {
+        self.0.hdr.encoding.version
+    }
+
+    pub fn name(&self) -> Option<String> {
+        unsafe { cstr_to_string(self.0.name) }
+    }
+
+    pub fn comp_dir(&self) -> Option<String> {
+        unsafe { cstr_to_string(self.0.comp_dir) }
+    }
+
+    pub fn producer(&self) -> Option<String> {
+        unsafe { cstr_to_string(self.0.producer) }
+    }
+
+    pub fn language(&self) -> Option<&'static str> {
+        unsafe { static_str(rz_bin_dwarf_lang(self.0.language)) }
+    }
+
+    pub fn low_pc(&self) -> u64 {
+        self.0.low_pc
+    }
+
+    /// DIEs of the unit in pre-order; use [`Die::depth`] to rebuild the tree.
+    pub fn dies(&self) -> impl Iterator<Item = Die<'a>> {
+        unsafe { vector_slice::<RzBinDwarfDie>(&self.0.dies) }
+            .iter()
+            .map(Die)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Die<'a>(pub &'a RzBinDwarfDie);
+
+impl<'a> Die<'a> {
+    pub fn offset(&self) -> u64 {
+        self.0.offset
+    }
+
+    pub fn tag(&self) -> u32 {
+        self.0.tag as _
+    }
+
+    pub fn tag_name(&self) -> Option<&'static str> {
+        unsafe { static_str(rz_bin_dwarf_tag(self.0.tag)) }
+    }
+
+    pub fn depth(&self) -> u64 {
+        self.0.depth as _
+    }
+
+    pub fn has_children(&self) -> bool {
+        self.0.has_children
+    }
+
+    pub fn attrs(&self) -> impl Iterator<Item = Attr<'a>> {
+        unsafe { vector_slice::<RzBinDwarfAttr>(&self.0.attrs) }
+            .iter()
+            .map(Attr)
+    }
+
+    pub fn attr(&self, at: u32) -> Option<Attr<'a>> {
+        self.attrs().find(|a| a.at() == at)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttrValue<'a> {
+    Address(u64),
+    Block(&'a [u8]),
+    Constant(i64),
+    UConstant(u64),
+    Flag(bool),
+    Reference(u64),
+    /// Inline or indirect string, resolve with [`Dwarf::attr_string`].
+    String,
+    /// Offset into a location or range list section.
+    SecOffset(u64),
+    Other,
+}
+
+#[derive(Clone, Copy)]
+pub struct Attr<'a>(pub &'a RzBinDwarfAttr);
+
+impl<'a> Attr<'a> {
+    pub fn at(&self) -> u32 {
+        self.0.at as _
+    }
+
+    pub fn name(&self) -> Option<&'static str> {
+        unsafe { static_str(rz_bin_dwarf_attr(self.0.at)) }
+    }
+
+    pub fn form(&self) -> u32 {
+        self.0.form as _
+    }
+
+    pub fn form_name(&self) -> Option<&'static str> {
+        unsafe { static_str(rz_bin_dwarf_form(self.0.form)) }
+    }
+
+    pub fn value(&self) -> AttrValue<'a> {
+        let attr = self.0;
+        unsafe {
+            match attr.value.kind {
+                RzBinDwarfAttrKind_RzBinDwarfAttr_Address => {
+                    AttrValue::Address(rz_bin_dwarf_attr_udata(attr))
+                }
+                RzBinDwarfAttrKind_RzBinDwarfAttr_Block => {
+                    match rz_bin_dwarf_attr_block(attr).as_ref() {
+                        Some(block) => AttrValue::Block(block_bytes(block)),
+                        None => AttrValue::Other,
+                    }
+                }
+                RzBinDwarfAttrKind_RzBinDwarfAttr_Constant => {
+                    AttrValue::Constant(rz_bin_dwarf_attr_sdata(attr))
+                }
+                RzBinDwarfAttrKind_RzBinDwarfAttr_UConstant => {
+                    AttrValue::UConstant(rz_bin_dwarf_attr_udata(attr))
+                }
+                RzBinDwarfAttrKind_RzBinDwarfAttr_Flag => {
+                    AttrValue::Flag(rz_bin_dwarf_attr_flag(attr))
+                }
+                RzBinDwarfAttrKind_RzBinDwarfAttr_Reference => {
+                    AttrValue::Reference(rz_bin_dwarf_attr_udata(attr))
+                }
+                RzBinDwarfAttrKind_RzBinDwarfAttr_String
+                | RzBinDwarfAttrKind_RzBinDwarfAttr_StrRef
+                | RzBinDwarfAttrKind_RzBinDwarfAttr_StrOffsetIndex => AttrValue::String,
+                RzBinDwarfAttrKind_RzBinDwarfAttr_LoclistPtr
+                | RzBinDwarfAttrKind_RzBinDwarfAttr_RangelistPtr => {
+                    AttrValue::SecOffset(rz_bin_dwarf_attr_udata(attr))
+                }
+                _ => AttrValue::Other,
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineRow {
+    pub address: u64,
+    pub file: Option<String>,
+    pub line: u32,
+    pub column: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Range {
+    pub begin: u64,
+    pub end: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArangeSet {
+    pub debug_info_offset: u64,
+    pub address_size: u8,
+    pub ranges: Vec<Range>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocListEntry {
+    pub range: Range,
+    /// The raw DWARF expression describing the location.
+    pub expression: Vec<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrapper::*;
+
+    #[test]
+    fn test_dwarf_from_file() {
+        let exe = std::env::current_exe().unwrap();
+        let core = Core::new();
+        let mut bf = core.open(exe).unwrap();
+        let Ok(dw) = bf.dw() else {
+            return;
+        };
+        let unit = dw.units().next().unwrap();
+        assert!(unit.dies().next().is_some());
+        assert!(!dw.lines().is_empty());
+    }
+
+    #[test]
+    fn test_dwarf_from_sections() {
+        let sections = DwarfSections {
+            abbrev: Some(&[]),
+            ..Default::default()
+        };
+        let dw = Dwarf::from_sections(sections, false).unwrap();
+        assert_eq!(dw.units().count(), 0);
+        assert!(dw.aranges().is_empty());
+
+        let sections = DwarfSections {
+            info: Some(&[0; 16]),
+            ..Default::default()
+        };
+        assert!(Dwarf::from_sections(sections, false).is_err());
+    }
+
+    #[test]
+    fn test_dwarf_from_sections_lists() {
+        let sections = DwarfSections {
+            abbrev: Some(&[]),
+            loc: Some(&[]),
+            ranges: Some(&[]),
+            ..Default::default()
+        };
+        let dw = Dwarf::from_sections(sections, false).unwrap();
+        assert!(!dw.raw().loclists.is_null());
+        assert!(!dw.raw().rnglists.is_null());
+    }
+}
@@ -0,0 +1,190 @@
+use std::ffi::NulError;
+use std::fmt;
+use std::str::Utf8Error;
+
+use crate::wrapper::{AsmError, CmdStatus};
+
+/// Errors returned by the wrapper.
+///
+/// `anyhow` users get a conversion for free through the `std::error::Error`
+/// impl, so `?` keeps working in functions returning `anyhow::Result`.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RizinError {
+    /// The bytes at `addr` don't decode to a valid instruction.
+    InvalidInstruction {
+        addr: u64,
+    },
+    /// Rizin returned null for `what`.
+    NullPointer {
+        what: &'static str,
+    },
+    /// A string is not valid UTF-8.
+    Utf8,
+    /// A string passed to rizin contains a nul byte.
+    Nul,
+    /// `key` doesn't exist when `value` is `None`, otherwise it rejected
+    /// `value`. `options` lists the accepted values when they are known.
+    ConfigKey {
+        key: String,
+        value: Option<String>,
+        options: Vec<String>,
+    },
+    ReadOnly {
+        key: String,
+    },
+    OpenFailed {
+        path: String,
+    },
+    PluginNotFound {
+        name: String,
+    },
+    /// No `kind`, such as a register or a type, is called `name`.
+    NotFound {
+        kind: &'static str,
+        name: String,
+    },
+    /// Reading or writing the IO at `addr` failed.
+    Io {
+        addr: u64,
+    },
+    /// Rizin refused an operation on the file descriptor `fd`.
+    Fd {
+        fd: i32,
+    },
+    /// Analyzing the function at `addr` failed.
+    Analysis {
+        addr: u64,
+    },
+    Xref {
+        from: u64,
+        to: u64,
+    },
+    /// Setting or renaming the flag `name` failed.
+    Flag {
+        name: String,
+    },
+    /// Writing the register `name` failed.
+    Register {
+        name: String,
+    },
+    /// The hash plugin `algo` failed.
+    Hash {
+        algo: String,
+    },
+    /// Rizin couldn't apply or write the signature file `path`.
+    Signature {
+        path: String,
+    },
+    /// Rizin failed to parse `what`.
+    Parse {
+        what: String,
+    },
+    Asm(AsmError),
+    /// The shell command `cmd` ended with `status`.
+    Cmd {
+        cmd: String,
+        status: CmdStatus,
+    },
+    InvalidArgument(String),
+    Unsupported(String),
+    Fmt,
+    #[cfg(feature = "serde")]
+    Json(serde_json::Error),
+    /// A rizin call failed without saying why, and without anything more
+    /// specific to report.
+    Failed(String),
+}
+
+impl fmt::Display for RizinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidInstruction { addr } => write!(f, "invalid instruction at {:#x}", addr),
+            Self::NullPointer { what } => write!(f, "{} is null", what),
+            Self::Utf8 => write!(f, "invalid utf-8"),
+            Self::Nul => write!(f, "string contains a nul byte"),
+            Self::ConfigKey {
+                key, value: None, ..
+            } => write!(f, "unknown config key {}", key),
+            Self::ConfigKey {
+                key,
+                value: Some(value),
+                options,
+            } if options.is_empty() => write!(f, "{} rejected value {:?}", key, value),
+            Self::ConfigKey {
+                key,
+                value: Some(value),
+                options,
+            } => write!(
+                f,
+                "invalid value {:?} for {}, valid options: {}",
+                value,
+                key,
+                options.join(", ")
+            ),
+            Self::ReadOnly { key } => write!(f, "{} is read-only", key),
+            Self::OpenFailed { path } => write!(f, "failed open {}", path),
+            Self::PluginNotFound { name } => write!(f, "no plugin {}", name),
+            Self::NotFound { kind, name } => write!(f, "unknown {} {}", kind, name),
+            Self::Io { addr } => write!(f, "failed io at {:#x}", addr),
+            Self::Fd { fd } => write!(f, "failed io on fd {}", fd),
+            Self::Analysis { addr } => write!(f, "failed analyze function at {:#x}", addr),
+            Self::Xref { from, to } => write!(f, "failed add xref {:#x} -> {:#x}", from, to),
+            Self::Flag { name } => write!(f, "failed set flag {}", name),
+            Self::Register { name } => write!(f, "failed set register {}", name),
+            Self::Hash { algo } => write!(f, "failed hash {}", algo),
+            Self::Signature { path } => write!(f, "failed signatures {}", path),
+            Self::Parse { what } => write!(f, "failed parse {}", what),
+            Self::Asm(e) => e.fmt(f),
+            Self::Cmd { cmd, status } => write!(f, "command `{}` failed: {:?}", cmd, status),
+            Self::InvalidArgument(msg) | Self::Unsupported(msg) | Self::Failed(msg) => {
+                f.write_str(msg)
+            }
+            Self::Fmt => write!(f, "formatting failed"),
+            #[cfg(feature = "serde")]
+            Self::Json(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for RizinError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Asm(e) => Some(e),
+            #[cfg(feature = "serde")]
+            Self::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<NulError> for RizinError {
+    fn from(_: NulError) -> Self {
+        Self::Nul
+    }
+}
+
+impl From<Utf8Error> for RizinError {
+    fn from(_: Utf8Error) -> Self {
+        Self::Utf8
+    }
+}
+
+impl From<fmt::Error> for RizinError {
+    fn from(_: fmt::Error) -> Self {
+        Self::Fmt
+    }
+}
+
+impl From<AsmError> for RizinError {
+    fn from(e: AsmError) -> Self {
+        Self::Asm(e)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<serde_json::Error> for RizinError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
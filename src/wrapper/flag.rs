@@ -0,0 +1,216 @@
+use std::ffi::{c_void, CString};
+use std::marker::PhantomData;
+use std::mem::offset_of;
+use std::ptr::{addr_of_mut, NonNull};
+
+use crate::wrapper::{cstr_to_string, Core, Result, RizinError};
+use crate::*;
+
+/// A snapshot of an `RzFlagItem`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Flag {
+    pub name: String,
+    pub realname: Option<String>,
+    pub offset: u64,
+    pub size: u64,
+    pub space: Option<String>,
+    pub alias: Option<String>,
+    pub comment: Option<String>,
+}
+
+impl Flag {
+    unsafe fn from_raw(item: &RzFlagItem) -> Self {
+        Self {
+            name: cstr_to_string(item.name).unwrap_or_default(),
+            realname: cstr_to_string(item.realname),
+            offset: item.offset,
+            size: item.size,
+            space: item.space.as_ref().and_then(|s| cstr_to_string(s.name)),
+            alias: cstr_to_string(item.alias),
+            comment: cstr_to_string(item.comment),
+        }
+    }
+}
+
+/// The flags of a [`Core`].
+pub struct Flags<'a> {
+    pub flags: NonNull<RzFlag>,
+    marker: PhantomData<&'a Core>,
+}
+
+impl Core {
+    pub fn flags(&self) -> Flags<'_> {
+        Flags {
+            flags: NonNull::new(unsafe { self.0.as_ref().flags }).unwrap(),
+            marker: PhantomData,
+        }
+    }
+}
+
+unsafe extern "C" fn collect_flag(fi: *mut RzFlagItem, user: *mut c_void) -> bool {
+    let flags = &mut *(user as *mut Vec<Flag>);
+    if let Some(fi) = fi.as_ref() {
+        flags.push(Flag::from_raw(fi));
+    }
+    true
+}
+
+impl Flags<'_> {
+    fn ptr(&self) -> *mut RzFlag {
+        self.flags.as_ptr()
+    }
+
+    fn item(&self, name: &str) -> Result<NonNull<RzFlagItem>> {
+        let cname = CString::new(name)?;
+        let item = unsafe { rz_flag_get(self.ptr(), cname.as_ptr()) };
+        NonNull::new(item).ok_or(RizinError::NotFound {
+            kind: "flag",
+            name: name.to_owned(),
+        })
+    }
+
+    /// Names of the flag spaces.
+    pub fn spaces(&self) -> Vec<String> {
+        let mut res = vec![];
+        unsafe {
+            let mut it = rz_rbtree_first((*self.ptr()).spaces.spaces);
+            while it.len > 0 {
+                let node = it.path[it.len as usize - 1] as *const u8;
+                let space = node.sub(offset_of!(RzSpace, rb)) as *const RzSpace;
+                res.extend(cstr_to_string((*space).name));
+                rz_rbtree_iter_next(addr_of_mut!(it));
+            }
+        }
+        res
+    }
+
+    /// All flags, in every space.
+    pub fn all(&self) -> Vec<Flag> {
+        let mut res: Vec<Flag> = vec![];
+        unsafe {
+            rz_flag_foreach(self.ptr(), Some(collect_flag), addr_of_mut!(res) as _);
+        }
+        res
+    }
+
+    pub fn in_space(&self, space: &str) -> Vec<Flag> {
+        self.all()
+            .into_iter()
+            .filter(|f| f.space.as_deref() == Some(space))
+            .collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<Flag> {
+        self.item(name)
+            .ok()
+            .map(|item| unsafe { Flag::from_raw(item.as_ref()) })
+    }
+
+    /// The flag at exactly `addr`.
+    pub fn at(&self, addr: u64) -> Option<Flag> {
+        let item = unsafe { rz_flag_get_i(self.ptr(), addr) };
+        unsafe { item.as_ref().map(|x| Flag::from_raw(x)) }
+    }
+
+    /// The closest flag at or before `addr` and the distance to it.
+    pub fn closest(&self, addr: u64) -> Option<(Flag, u64)> {
+        let item = unsafe { rz_flag_get_at(self.ptr(), addr, true) };
+        unsafe { item.as_ref() }.map(|x| (unsafe { Flag::from_raw(x) }, addr - x.offset))
+    }
+
+    /// Formats `addr` as `flag + delta` like rizin does, or just `flag` on a hit.
+    pub fn describe(&self, addr: u64) -> Option<String> {
+        self.closest(addr).map(|(flag, delta)| {
+            if delta == 0 {
+                flag.name
+            } else {
+                format!("{} + {}", flag.name, delta)
+            }
+        })
+    }
+
+    /// Creates or moves the flag `name` in the current space.
+    pub fn set(&self, name: &str, addr: u64, size: u64) -> Result<Flag> {
+        let cname = CString::new(name)?;
+        let item = unsafe { rz_flag_set(self.ptr(), cname.as_ptr(), addr, size) };
+        unsafe { item.as_ref() }
+            .map(|x| unsafe { Flag::from_raw(x) })
+            .ok_or(RizinError::Flag {
+                name: name.to_owned(),
+            })
+    }
+
+    /// Like [`Flags::set`] but in `space`, which is created if needed.
+    pub fn set_in(&self, space: &str, name: &str, addr: u64, size: u64) -> Result<Flag> {
+        let cspace = CString::new(space)?;
+        unsafe {
+            if !rz_flag_space_push(self.ptr(), cspace.as_ptr()) {
+                return Err(RizinError::Flag {
+                    name: name.to_owned(),
+                });
+            }
+        }
+        let res = self.set(name, addr, size);
+        unsafe {
+            rz_flag_space_pop(self.ptr());
+        }
+        res
+    }
+
+    pub fn rename(&self, name: &str, new_name: &str) -> Result<()> {
+        let item = self.item(name)?;
+        let cnew = CString::new(new_name)?;
+        if unsafe { rz_flag_rename(self.ptr(), item.as_ptr(), cnew.as_ptr()) } {
+            Ok(())
+        } else {
+            Err(RizinError::Flag {
+                name: new_name.to_owned(),
+            })
+        }
+    }
+
+    pub fn set_comment(&self, name: &str, comment: &str) -> Result<()> {
+        let item = self.item(name)?;
+        let ccomment = CString::new(comment)?;
+        unsafe { rz_flag_item_set_comment(item.as_ptr(), ccomment.as_ptr()) };
+        Ok(())
+    }
+
+    pub fn set_alias(&self, name: &str, alias: &str) -> Result<()> {
+        let item = self.item(name)?;
+        let calias = CString::new(alias)?;
+        unsafe { rz_flag_item_set_alias(item.as_ptr(), calias.as_ptr()) };
+        Ok(())
+    }
+
+    /// Deletes the flag `name`, returns whether it existed.
+    pub fn remove(&self, name: &str) -> bool {
+        let Ok(cname) = CString::new(name) else {
+            return false;
+        };
+        unsafe { rz_flag_unset_name(self.ptr(), cname.as_ptr()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrapper::*;
+
+    #[test]
+    fn test_flags() {
+        let core = Core::new();
+        let flags = core.flags();
+        flags.set_in("symbols", "sym.main", 0x1000, 0x20).unwrap();
+        assert!(flags.spaces().iter().any(|s| s == "symbols"));
+        assert_eq!(flags.in_space("symbols").len(), 1);
+        assert_eq!(flags.describe(0x1000).unwrap(), "sym.main");
+        assert_eq!(flags.describe(0x1010).unwrap(), "sym.main + 16");
+        flags.rename("sym.main", "main").unwrap();
+        flags.set_comment("main", "entry").unwrap();
+        let flag = flags.at(0x1000).unwrap();
+        assert_eq!(flag.name, "main");
+        assert_eq!(flag.comment.as_deref(), Some("entry"));
+        assert!(flags.remove("main"));
+        assert!(flags.get("main").is_none());
+    }
+}
@@ -0,0 +1,246 @@
+use std::ffi::CString;
+use std::marker::PhantomData;
+use std::ptr::{addr_of_mut, NonNull};
+
+use crate::wrapper::{cstr_to_string, ht_values, Core, Result, RizinError};
+use crate::*;
+
+/// Size of the reads when hashing IO ranges.
+const CHUNK_SIZE: u64 = 0x10000;
+
+/// Shannon entropy of `data` in bits per byte, between 0 and 8.
+pub fn entropy(data: &[u8]) -> f64 {
+    unsafe { rz_hash_entropy(data.as_ptr(), data.len() as _) }
+}
+
+/// An `RzHash` instance, either standalone or borrowed from a [`Core`].
+pub struct Hash<'a> {
+    pub inner: NonNull<RzHash>,
+    owned: bool,
+    marker: PhantomData<&'a RzCore>,
+}
+
+impl Hash<'static> {
+    pub fn new() -> Self {
+        Self {
+            inner: NonNull::new(unsafe { rz_hash_new() }).unwrap(),
+            owned: true,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl Default for Hash<'static> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Hash<'_> {
+    fn drop(&mut self) {
+        if self.owned {
+            unsafe {
+                rz_hash_free(self.inner.as_ptr());
+            }
+        }
+    }
+}
+
+impl Core {
+    pub fn hash(&self) -> Hash<'_> {
+        Hash {
+            inner: NonNull::new(unsafe { self.0.as_ref().hash }).unwrap(),
+            owned: false,
+            marker: PhantomData,
+        }
+    }
+
+    /// Digest of `len` bytes of IO at `addr`.
+    pub fn hash_range(&self, algo: &str, addr: u64, len: u64) -> Result<Vec<u8>> {
+        let hash = self.hash();
+        let mut hasher = hash.hasher(algo)?;
+        let io = self.io();
+        let mut buf = vec![];
+        let mut off = 0;
+        while off < len {
+            buf.resize((len - off).min(CHUNK_SIZE) as usize, 0);
+            io.read_at(addr + off, &mut buf)?;
+            hasher.update(&buf)?;
+            off += buf.len() as u64;
+        }
+        hasher.finish()
+    }
+
+    /// Entropy of each `block_size` block of IO in `[from, to)`, as `(addr, entropy)`.
+    pub fn entropy_map(&self, from: u64, to: u64, block_size: u64) -> Result<Vec<(u64, f64)>> {
+        if block_size == 0 {
+            return Err(RizinError::InvalidArgument("block size is 0".to_owned()));
+        }
+        let io = self.io();
+        let mut res = vec![];
+        let mut buf = vec![];
+        let mut addr = from;
+        while addr < to {
+            buf.resize((to - addr).min(block_size) as usize, 0);
+            io.read_at(addr, &mut buf)?;
+            res.push((addr, entropy(&buf)));
+            addr += buf.len() as u64;
+        }
+        Ok(res)
+    }
+}
+
+impl Hash<'_> {
+    /// Names of the supported algorithms, sorted.
+    pub fn algorithms(&self) -> Vec<String> {
+        let mut res = unsafe { ht_values::<RzHashPlugin, _>((*self.inner.as_ptr()).plugins, self) }
+            .into_iter()
+            .filter_map(|p| unsafe { cstr_to_string(p.name) })
+            .collect::<Vec<_>>();
+        res.sort();
+        res
+    }
+
+    pub fn hasher(&self, algo: &str) -> Result<Hasher<'_>> {
+        let name = CString::new(algo)?;
+        unsafe {
+            let cfg = NonNull::new(rz_hash_cfg_new(self.inner.as_ptr()))
+                .ok_or(RizinError::NullPointer { what: "hash cfg" })?;
+            let hasher = Hasher {
+                cfg,
+                name,
+                marker: PhantomData,
+            };
+            if !rz_hash_cfg_configure(cfg.as_ptr(), hasher.name.as_ptr()) {
+                return Err(RizinError::PluginNotFound {
+                    name: algo.to_owned(),
+                });
+            }
+            if !rz_hash_cfg_init(cfg.as_ptr()) {
+                return Err(RizinError::Hash {
+                    algo: algo.to_owned(),
+                });
+            }
+            Ok(hasher)
+        }
+    }
+
+    /// Digest of `data` with `algo`, e.g. `"md5"` or `"crc32"`.
+    pub fn digest(&self, algo: &str, data: &[u8]) -> Result<Vec<u8>> {
+        let mut hasher = self.hasher(algo)?;
+        hasher.update(data)?;
+        hasher.finish()
+    }
+
+    /// Like [`Hash::digest`], formatted the way rizin prints it.
+    pub fn digest_string(&self, algo: &str, data: &[u8]) -> Result<String> {
+        let name = CString::new(algo)?;
+        let mut size = 0;
+        unsafe {
+            let s = rz_hash_cfg_calculate_small_block_string(
+                self.inner.as_ptr(),
+                name.as_ptr(),
+                data.as_ptr(),
+                data.len() as _,
+                addr_of_mut!(size),
+                false,
+            );
+            let res = cstr_to_string(s);
+            free(s as _);
+            res.ok_or(RizinError::PluginNotFound {
+                name: algo.to_owned(),
+            })
+        }
+    }
+}
+
+/// Incremental hashing with one algorithm, see [`Hash::hasher`].
+pub struct Hasher<'a> {
+    cfg: NonNull<RzHashCfg>,
+    name: CString,
+    marker: PhantomData<&'a RzHash>,
+}
+
+impl Hasher<'_> {
+    fn error(&self) -> RizinError {
+        RizinError::Hash {
+            algo: self.name.to_string_lossy().into_owned(),
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) -> Result<()> {
+        if unsafe { rz_hash_cfg_update(self.cfg.as_ptr(), data.as_ptr(), data.len() as _) } {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    pub fn finish(self) -> Result<Vec<u8>> {
+        unsafe {
+            if !rz_hash_cfg_final(self.cfg.as_ptr()) {
+                return Err(self.error());
+            }
+            let mut size = 0;
+            let res =
+                rz_hash_cfg_get_result(self.cfg.as_ptr(), self.name.as_ptr(), addr_of_mut!(size));
+            if res.is_null() {
+                return Err(RizinError::NullPointer {
+                    what: "hash result",
+                });
+            }
+            Ok(std::slice::from_raw_parts(res, size as _).to_vec())
+        }
+    }
+}
+
+impl Drop for Hasher<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            rz_hash_cfg_free(self.cfg.as_ptr());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrapper::*;
+
+    #[test]
+    fn test_digest() {
+        let hash = Hash::new();
+        assert!(hash.algorithms().iter().any(|a| a == "sha256"));
+        assert_eq!(
+            hash.digest_string("md5", b"abc").unwrap(),
+            "900150983cd24fb0d6963f7d28e17f72"
+        );
+        let mut hasher = hash.hasher("md5").unwrap();
+        hasher.update(b"a").unwrap();
+        hasher.update(b"bc").unwrap();
+        assert_eq!(
+            hasher.finish().unwrap(),
+            hash.digest("md5", b"abc").unwrap()
+        );
+        assert!(hash.hasher("nope").is_err());
+    }
+
+    #[test]
+    fn test_entropy() {
+        assert_eq!(entropy(&[0; 64]), 0.0);
+        let all = (0..=255).collect::<Vec<u8>>();
+        assert!((entropy(&all) - 8.0).abs() < 1e-9);
+
+        let core = Core::new();
+        let io = core.io();
+        io.open("malloc://0x200", Perm::R | Perm::W).unwrap();
+        io.write_at(0x100, &all).unwrap();
+        let map = core.entropy_map(0, 0x200, 0x100).unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map[0], (0, 0.0));
+        assert!(map[1].1 > 7.9);
+        assert_eq!(
+            core.hash_range("md5", 0x100, 0x100).unwrap(),
+            Hash::new().digest("md5", &all).unwrap()
+        );
+    }
+}
@@ -0,0 +1,1014 @@
+//! Owned Rust mirror of the RzIL op tree (`RzILOpEffect`/`RzILOpPure`).
+//!
+//! [`Effect::from_raw`] copies a C tree into the AST, [`Effect::to_raw`] builds
+//! a fresh C tree from it. Float ops without a variant here come back as
+//! [`Pure::Unsupported`] and cannot be converted back.
+
+use std::ffi::{c_char, CStr, CString};
+use std::fmt;
+use std::fmt::Display;
+use std::mem::ManuallyDrop;
+use std::ptr::{addr_of_mut, NonNull};
+
+use crate::wrapper::{AnalysisOp, Result, RizinError, StrBuf};
+use crate::*;
+
+/// A constant bitvector, stored little-endian.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitVector {
+    pub width: u32,
+    pub bytes: Vec<u8>,
+}
+
+impl BitVector {
+    pub fn from_u64(width: u32, value: u64) -> Self {
+        let mut bytes = value.to_le_bytes().to_vec();
+        bytes.resize((width as usize).div_ceil(8), 0);
+        let mut bv = Self { width, bytes };
+        bv.mask();
+        bv
+    }
+
+    /// The value truncated to 64 bits.
+    pub fn to_u64(&self) -> u64 {
+        let mut buf = [0u8; 8];
+        let n = self.bytes.len().min(8);
+        buf[..n].copy_from_slice(&self.bytes[..n]);
+        u64::from_le_bytes(buf)
+    }
+
+    fn mask(&mut self) {
+        let rem = self.width % 8;
+        if rem != 0 {
+            if let Some(last) = self.bytes.last_mut() {
+                *last &= (1u8 << rem) - 1;
+            }
+        }
+    }
+
+    pub(crate) unsafe fn from_raw(bv: *const RzBitVector) -> Result<Self> {
+        if bv.is_null() {
+            return Err(RizinError::NullPointer { what: "bitvector" });
+        }
+        let width = rz_bv_len(bv);
+        let mut bytes = vec![0u8; (width as usize).div_ceil(8)];
+        for i in 0..width {
+            if rz_bv_get(bv, i) {
+                bytes[(i / 8) as usize] |= 1 << (i % 8);
+            }
+        }
+        Ok(Self { width, bytes })
+    }
+
+    pub(crate) unsafe fn to_raw(&self) -> Result<*mut RzBitVector> {
+        let bv = if self.width <= 64 {
+            rz_bv_new_from_ut64(self.width, self.to_u64())
+        } else {
+            let bv = rz_bv_new(self.width);
+            if !bv.is_null() {
+                for i in 0..self.width {
+                    let bit = self.bytes[(i / 8) as usize] >> (i % 8) & 1 == 1;
+                    rz_bv_set(bv, i, bit);
+                }
+            }
+            bv
+        };
+        if bv.is_null() {
+            Err(RizinError::NullPointer { what: "bitvector" })
+        } else {
+            Ok(bv)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VarKind {
+    Global,
+    Local,
+    LocalImmutable,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BoolOp {
+    And,
+    Or,
+    Xor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Sdiv,
+    Mod,
+    Smod,
+    LogAnd,
+    LogOr,
+    LogXor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CmpOp {
+    Eq,
+    Sle,
+    Ule,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShiftDir {
+    Left,
+    Right,
+}
+
+/// Float ops taking a single float operand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FUnOp {
+    Fbits,
+    IsFinite,
+    IsNan,
+    IsInf,
+    IsFzero,
+    IsFneg,
+    IsFpos,
+    Fneg,
+    Fabs,
+    Fsucc,
+    Fpred,
+}
+
+/// Float ops taking two float operands and a rounding mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FBinOp {
+    Fadd,
+    Fsub,
+    Fmul,
+    Fdiv,
+    Fmod,
+}
+
+pub type Mem = RzILMemIndex;
+pub type FloatFormat = RzFloatFormat;
+pub type RoundingMode = RzFloatRMode;
+
+/// Pure (value-producing) RzIL ops.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Pure {
+    Var {
+        name: String,
+        kind: VarKind,
+    },
+    Ite {
+        cond: Box<Pure>,
+        x: Box<Pure>,
+        y: Box<Pure>,
+    },
+    Let {
+        name: String,
+        exp: Box<Pure>,
+        body: Box<Pure>,
+    },
+    Bool(bool),
+    Inv(Box<Pure>),
+    BoolBin {
+        op: BoolOp,
+        x: Box<Pure>,
+        y: Box<Pure>,
+    },
+    Bitv(BitVector),
+    Msb(Box<Pure>),
+    Lsb(Box<Pure>),
+    IsZero(Box<Pure>),
+    Neg(Box<Pure>),
+    LogNot(Box<Pure>),
+    Binary {
+        op: BinOp,
+        x: Box<Pure>,
+        y: Box<Pure>,
+    },
+    Shift {
+        dir: ShiftDir,
+        fill: Box<Pure>,
+        x: Box<Pure>,
+        y: Box<Pure>,
+    },
+    Cmp {
+        op: CmpOp,
+        x: Box<Pure>,
+        y: Box<Pure>,
+    },
+    Cast {
+        length: u32,
+        fill: Box<Pure>,
+        val: Box<Pure>,
+    },
+    Append {
+        high: Box<Pure>,
+        low: Box<Pure>,
+    },
+    Load {
+        mem: Mem,
+        key: Box<Pure>,
+    },
+    LoadW {
+        mem: Mem,
+        key: Box<Pure>,
+        bits: u32,
+    },
+    Float {
+        format: FloatFormat,
+        bv: Box<Pure>,
+    },
+    FUnary {
+        op: FUnOp,
+        f: Box<Pure>,
+    },
+    FBinary {
+        op: FBinOp,
+        mode: RoundingMode,
+        x: Box<Pure>,
+        y: Box<Pure>,
+    },
+    Forder {
+        x: Box<Pure>,
+        y: Box<Pure>,
+    },
+    Fround {
+        mode: RoundingMode,
+        f: Box<Pure>,
+    },
+    Fsqrt {
+        mode: RoundingMode,
+        f: Box<Pure>,
+    },
+    FcastInt {
+        signed: bool,
+        length: u32,
+        mode: RoundingMode,
+        f: Box<Pure>,
+    },
+    FcastFloat {
+        signed: bool,
+        format: FloatFormat,
+        mode: RoundingMode,
+        bv: Box<Pure>,
+    },
+    Fconvert {
+        format: FloatFormat,
+        mode: RoundingMode,
+        f: Box<Pure>,
+    },
+    /// An op code without a Rust variant.
+    Unsupported(u32),
+}
+
+/// Effect (state-changing) RzIL ops.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Effect {
+    Empty,
+    Nop,
+    Set {
+        var: String,
+        is_local: bool,
+        x: Pure,
+    },
+    Jmp(Pure),
+    Goto(String),
+    Seq(Box<Effect>, Box<Effect>),
+    Blk {
+        label: Option<String>,
+        data: Box<Effect>,
+        ctrl: Box<Effect>,
+    },
+    Repeat {
+        cond: Pure,
+        data: Box<Effect>,
+    },
+    Branch {
+        cond: Pure,
+        t: Box<Effect>,
+        f: Box<Effect>,
+    },
+    Store {
+        mem: Mem,
+        key: Pure,
+        value: Pure,
+    },
+    StoreW {
+        mem: Mem,
+        key: Pure,
+        value: Pure,
+    },
+}
+
+unsafe fn string(ptr: *const c_char) -> Result<String> {
+    if ptr.is_null() {
+        Err(RizinError::NullPointer { what: "il string" })
+    } else {
+        Ok(CStr::from_ptr(ptr).to_str()?.to_owned())
+    }
+}
+
+unsafe fn pure(p: *const RzILOpPure) -> Result<Box<Pure>> {
+    let p = p
+        .as_ref()
+        .ok_or(RizinError::NullPointer { what: "il pure" })?;
+    Ok(Box::new(Pure::from_raw(p)?))
+}
+
+unsafe fn effect(e: *const RzILOpEffect) -> Result<Box<Effect>> {
+    let e = e
+        .as_ref()
+        .ok_or(RizinError::NullPointer { what: "il effect" })?;
+    Ok(Box::new(Effect::from_raw(e)?))
+}
+
+impl Pure {
+    /// Copies a C op tree.
+    ///
+    /// # Safety
+    /// `p` must be a well-formed op tree.
+    pub unsafe fn from_raw(p: &RzILOpPure) -> Result<Self> {
+        let op = &p.op;
+        let res = match p.code {
+            RzILOpPureCode_RZ_IL_OP_VAR => Pure::Var {
+                name: string(op.var.v)?,
+                kind: match op.var.kind {
+                    RzILVarKind_RZ_IL_VAR_KIND_LOCAL => VarKind::Local,
+                    RzILVarKind_RZ_IL_VAR_KIND_LOCAL_IMMUTABLE => VarKind::LocalImmutable,
+                    _ => VarKind::Global,
+                },
+            },
+            RzILOpPureCode_RZ_IL_OP_ITE => Pure::Ite {
+                cond: pure(op.ite.condition)?,
+                x: pure(op.ite.x)?,
+                y: pure(op.ite.y)?,
+            },
+            RzILOpPureCode_RZ_IL_OP_LET => Pure::Let {
+                name: string(op.let_.name)?,
+                exp: pure(op.let_.exp)?,
+                body: pure(op.let_.body)?,
+            },
+            RzILOpPureCode_RZ_IL_OP_B0 => Pure::Bool(false),
+            RzILOpPureCode_RZ_IL_OP_B1 => Pure::Bool(true),
+            RzILOpPureCode_RZ_IL_OP_INV => Pure::Inv(pure(op.boolinv.x)?),
+            RzILOpPureCode_RZ_IL_OP_AND => Pure::BoolBin {
+                op: BoolOp::And,
+                x: pure(op.booland.x)?,
+                y: pure(op.booland.y)?,
+            },
+            RzILOpPureCode_RZ_IL_OP_OR => Pure::BoolBin {
+                op: BoolOp::Or,
+                x: pure(op.boolor.x)?,
+                y: pure(op.boolor.y)?,
+            },
+            RzILOpPureCode_RZ_IL_OP_XOR => Pure::BoolBin {
+                op: BoolOp::Xor,
+                x: pure(op.boolxor.x)?,
+                y: pure(op.boolxor.y)?,
+            },
+            RzILOpPureCode_RZ_IL_OP_BITV => Pure::Bitv(BitVector::from_raw(op.bitv.value)?),
+            RzILOpPureCode_RZ_IL_OP_MSB => Pure::Msb(pure(op.msb.bv)?),
+            RzILOpPureCode_RZ_IL_OP_LSB => Pure::Lsb(pure(op.lsb.bv)?),
+            RzILOpPureCode_RZ_IL_OP_IS_ZERO => Pure::IsZero(pure(op.is_zero.bv)?),
+            RzILOpPureCode_RZ_IL_OP_NEG => Pure::Neg(pure(op.neg.bv)?),
+            RzILOpPureCode_RZ_IL_OP_LOGNOT => Pure::LogNot(pure(op.lognot.bv)?),
+            RzILOpPureCode_RZ_IL_OP_ADD => binary(BinOp::Add, &op.add)?,
+            RzILOpPureCode_RZ_IL_OP_SUB => binary(BinOp::Sub, &op.sub)?,
+            RzILOpPureCode_RZ_IL_OP_MUL => binary(BinOp::Mul, &op.mul)?,
+            RzILOpPureCode_RZ_IL_OP_DIV => binary(BinOp::Div, &op.div)?,
+            RzILOpPureCode_RZ_IL_OP_SDIV => binary(BinOp::Sdiv, &op.sdiv)?,
+            RzILOpPureCode_RZ_IL_OP_MOD => binary(BinOp::Mod, &op.mod_)?,
+            RzILOpPureCode_RZ_IL_OP_SMOD => binary(BinOp::Smod, &op.smod)?,
+            RzILOpPureCode_RZ_IL_OP_LOGAND => binary(BinOp::LogAnd, &op.logand)?,
+            RzILOpPureCode_RZ_IL_OP_LOGOR => binary(BinOp::LogOr, &op.logor)?,
+            RzILOpPureCode_RZ_IL_OP_LOGXOR => binary(BinOp::LogXor, &op.logxor)?,
+            RzILOpPureCode_RZ_IL_OP_SHIFTR => Pure::Shift {
+                dir: ShiftDir::Right,
+                fill: pure(op.shiftr.fill_bit)?,
+                x: pure(op.shiftr.x)?,
+                y: pure(op.shiftr.y)?,
+            },
+            RzILOpPureCode_RZ_IL_OP_SHIFTL => Pure::Shift {
+                dir: ShiftDir::Left,
+                fill: pure(op.shiftl.fill_bit)?,
+                x: pure(op.shiftl.x)?,
+                y: pure(op.shiftl.y)?,
+            },
+            RzILOpPureCode_RZ_IL_OP_EQ => Pure::Cmp {
+                op: CmpOp::Eq,
+                x: pure(op.eq.x)?,
+                y: pure(op.eq.y)?,
+            },
+            RzILOpPureCode_RZ_IL_OP_SLE => Pure::Cmp {
+                op: CmpOp::Sle,
+                x: pure(op.sle.x)?,
+                y: pure(op.sle.y)?,
+            },
+            RzILOpPureCode_RZ_IL_OP_ULE => Pure::Cmp {
+                op: CmpOp::Ule,
+                x: pure(op.ule.x)?,
+                y: pure(op.ule.y)?,
+            },
+            RzILOpPureCode_RZ_IL_OP_CAST => Pure::Cast {
+                length: op.cast.length,
+                fill: pure(op.cast.fill)?,
+                val: pure(op.cast.val)?,
+            },
+            RzILOpPureCode_RZ_IL_OP_APPEND => Pure::Append {
+                high: pure(op.append.high)?,
+                low: pure(op.append.low)?,
+            },
+            RzILOpPureCode_RZ_IL_OP_LOAD => Pure::Load {
+                mem: op.load.mem,
+                key: pure(op.load.key)?,
+            },
+            RzILOpPureCode_RZ_IL_OP_LOADW => Pure::LoadW {
+                mem: op.loadw.mem,
+                key: pure(op.loadw.key)?,
+                bits: op.loadw.n_bits,
+            },
+            RzILOpPureCode_RZ_IL_OP_FLOAT => Pure::Float {
+                format: op.float_.r,
+                bv: pure(op.float_.bv)?,
+            },
+            RzILOpPureCode_RZ_IL_OP_FBITS => funary(FUnOp::Fbits, op.fbits.f)?,
+            RzILOpPureCode_RZ_IL_OP_IS_FINITE => funary(FUnOp::IsFinite, op.is_finite.f)?,
+            RzILOpPureCode_RZ_IL_OP_IS_NAN => funary(FUnOp::IsNan, op.is_nan.f)?,
+            RzILOpPureCode_RZ_IL_OP_IS_INF => funary(FUnOp::IsInf, op.is_inf.f)?,
+            RzILOpPureCode_RZ_IL_OP_IS_FZERO => funary(FUnOp::IsFzero, op.is_fzero.f)?,
+            RzILOpPureCode_RZ_IL_OP_IS_FNEG => funary(FUnOp::IsFneg, op.is_fneg.f)?,
+            RzILOpPureCode_RZ_IL_OP_IS_FPOS => funary(FUnOp::IsFpos, op.is_fpos.f)?,
+            RzILOpPureCode_RZ_IL_OP_FNEG => funary(FUnOp::Fneg, op.fneg.f)?,
+            RzILOpPureCode_RZ_IL_OP_FABS => funary(FUnOp::Fabs, op.fabs.f)?,
+            RzILOpPureCode_RZ_IL_OP_FSUCC => funary(FUnOp::Fsucc, op.fsucc.f)?,
+            RzILOpPureCode_RZ_IL_OP_FPRED => funary(FUnOp::Fpred, op.fpred.f)?,
+            RzILOpPureCode_RZ_IL_OP_FADD => fbinary(FBinOp::Fadd, &op.fadd)?,
+            RzILOpPureCode_RZ_IL_OP_FSUB => fbinary(FBinOp::Fsub, &op.fsub)?,
+            RzILOpPureCode_RZ_IL_OP_FMUL => fbinary(FBinOp::Fmul, &op.fmul)?,
+            RzILOpPureCode_RZ_IL_OP_FDIV => fbinary(FBinOp::Fdiv, &op.fdiv)?,
+            RzILOpPureCode_RZ_IL_OP_FMOD => fbinary(FBinOp::Fmod, &op.fmod)?,
+            RzILOpPureCode_RZ_IL_OP_FORDER => Pure::Forder {
+                x: pure(op.forder.x)?,
+                y: pure(op.forder.y)?,
+            },
+            RzILOpPureCode_RZ_IL_OP_FROUND => Pure::Fround {
+                mode: op.fround.rmode,
+                f: pure(op.fround.f)?,
+            },
+            RzILOpPureCode_RZ_IL_OP_FSQRT => Pure::Fsqrt {
+                mode: op.fsqrt.rmode,
+                f: pure(op.fsqrt.f)?,
+            },
+            RzILOpPureCode_RZ_IL_OP_FCAST_INT | RzILOpPureCode_RZ_IL_OP_FCAST_SINT => {
+                let args = if p.code == RzILOpPureCode_RZ_IL_OP_FCAST_INT {
+                    &op.fcast_int
+                } else {
+                    &op.fcast_sint
+                };
+                Pure::FcastInt {
+                    signed: p.code == RzILOpPureCode_RZ_IL_OP_FCAST_SINT,
+                    length: args.length,
+                    mode: args.mode,
+                    f: pure(args.f)?,
+                }
+            }
+            RzILOpPureCode_RZ_IL_OP_FCAST_FLOAT | RzILOpPureCode_RZ_IL_OP_FCAST_SFLOAT => {
+                let args = if p.code == RzILOpPureCode_RZ_IL_OP_FCAST_FLOAT {
+                    &op.fcast_float
+                } else {
+                    &op.fcast_sfloat
+                };
+                Pure::FcastFloat {
+                    signed: p.code == RzILOpPureCode_RZ_IL_OP_FCAST_SFLOAT,
+                    format: args.format,
+                    mode: args.mode,
+                    bv: pure(args.bv)?,
+                }
+            }
+            RzILOpPureCode_RZ_IL_OP_FCONVERT => Pure::Fconvert {
+                format: op.fconvert.format,
+                mode: op.fconvert.mode,
+                f: pure(op.fconvert.f)?,
+            },
+            code => Pure::Unsupported(code as _),
+        };
+        Ok(res)
+    }
+}
+
+unsafe fn binary(op: BinOp, args: &RzILOpArgsAdd) -> Result<Pure> {
+    Ok(Pure::Binary {
+        op,
+        x: pure(args.x)?,
+        y: pure(args.y)?,
+    })
+}
+
+unsafe fn funary(op: FUnOp, f: *const RzILOpFloat) -> Result<Pure> {
+    Ok(Pure::FUnary { op, f: pure(f)? })
+}
+
+unsafe fn fbinary(op: FBinOp, args: &RzILOpArgsFadd) -> Result<Pure> {
+    Ok(Pure::FBinary {
+        op,
+        mode: args.rmode,
+        x: pure(args.x)?,
+        y: pure(args.y)?,
+    })
+}
+
+impl Effect {
+    /// Copies a C op tree.
+    ///
+    /// # Safety
+    /// `e` must be a well-formed op tree.
+    pub unsafe fn from_raw(e: &RzILOpEffect) -> Result<Self> {
+        let op = &e.op;
+        let res = match e.code {
+            RzILOpEffectCode_RZ_IL_OP_EMPTY => Effect::Empty,
+            RzILOpEffectCode_RZ_IL_OP_NOP => Effect::Nop,
+            RzILOpEffectCode_RZ_IL_OP_SET => Effect::Set {
+                var: string(op.set.v)?,
+                is_local: op.set.is_local,
+                x: *pure(op.set.x)?,
+            },
+            RzILOpEffectCode_RZ_IL_OP_JMP => Effect::Jmp(*pure(op.jmp.dst)?),
+            RzILOpEffectCode_RZ_IL_OP_GOTO => Effect::Goto(string(op.goto_.lbl)?),
+            RzILOpEffectCode_RZ_IL_OP_SEQ => Effect::Seq(effect(op.seq.x)?, effect(op.seq.y)?),
+            RzILOpEffectCode_RZ_IL_OP_BLK => Effect::Blk {
+                label: string(op.blk.label).ok(),
+                data: effect(op.blk.data_eff)?,
+                ctrl: effect(op.blk.ctrl_eff)?,
+            },
+            RzILOpEffectCode_RZ_IL_OP_REPEAT => Effect::Repeat {
+                cond: *pure(op.repeat.condition)?,
+                data: effect(op.repeat.data_eff)?,
+            },
+            RzILOpEffectCode_RZ_IL_OP_BRANCH => Effect::Branch {
+                cond: *pure(op.branch.condition)?,
+                t: effect(op.branch.true_eff)?,
+                f: effect(op.branch.false_eff)?,
+            },
+            RzILOpEffectCode_RZ_IL_OP_STORE => Effect::Store {
+                mem: op.store.mem,
+                key: *pure(op.store.key)?,
+                value: *pure(op.store.value)?,
+            },
+            RzILOpEffectCode_RZ_IL_OP_STOREW => Effect::StoreW {
+                mem: op.storew.mem,
+                key: *pure(op.storew.key)?,
+                value: *pure(op.storew.value)?,
+            },
+            code => {
+                return Err(RizinError::Unsupported(format!(
+                    "unknown il effect code {}",
+                    code
+                )))
+            }
+        };
+        Ok(res)
+    }
+
+    /// Builds a new C op tree with the same structure.
+    pub fn to_raw(&self) -> Result<IlEffect> {
+        let mut strings = vec![];
+        let op = unsafe { build_effect(self, &mut strings)? };
+        Ok(IlEffect {
+            op: op.into_raw(),
+            strings,
+        })
+    }
+}
+
+impl AnalysisOp {
+    pub fn il(&self) -> Result<Effect> {
+        let il_op =
+            unsafe { self.0.il_op.as_ref() }.ok_or(RizinError::NullPointer { what: "il_op" })?;
+        unsafe { Effect::from_raw(il_op) }
+    }
+}
+
+/// A C effect tree built from an [`Effect`]. The names it references are
+/// borrowed by the C ops, so they are kept alive here.
+pub struct IlEffect {
+    pub op: NonNull<RzILOpEffect>,
+    strings: Vec<CString>,
+}
+
+impl IlEffect {
+    pub fn to_effect(&self) -> Result<Effect> {
+        unsafe { Effect::from_raw(self.op.as_ref()) }
+    }
+}
+
+impl Display for IlEffect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut sb = StrBuf::new();
+        unsafe {
+            rz_il_op_effect_stringify(self.op.as_ptr(), addr_of_mut!(sb.0), false);
+        }
+        Display::fmt(&sb, f)
+    }
+}
+
+impl Drop for IlEffect {
+    fn drop(&mut self) {
+        unsafe {
+            rz_il_op_effect_free(self.op.as_ptr());
+        }
+    }
+}
+
+/// Frees a partially built subtree if a sibling fails to build.
+struct Guard<T: Free>(NonNull<T>);
+
+trait Free {
+    unsafe fn free(p: *mut Self);
+}
+
+impl Free for RzILOpPure {
+    unsafe fn free(p: *mut Self) {
+        rz_il_op_pure_free(p)
+    }
+}
+
+impl Free for RzILOpEffect {
+    unsafe fn free(p: *mut Self) {
+        rz_il_op_effect_free(p)
+    }
+}
+
+impl<T: Free> Guard<T> {
+    fn new(p: *mut T) -> Result<Self> {
+        NonNull::new(p)
+            .map(Guard)
+            .ok_or(RizinError::Failed("failed build il op".to_owned()))
+    }
+
+    fn into_raw(self) -> NonNull<T> {
+        ManuallyDrop::new(self).0
+    }
+
+    fn raw(self) -> *mut T {
+        self.into_raw().as_ptr()
+    }
+}
+
+impl<T: Free> Drop for Guard<T> {
+    fn drop(&mut self) {
+        unsafe { T::free(self.0.as_ptr()) }
+    }
+}
+
+fn intern(strings: &mut Vec<CString>, s: &str) -> Result<*const c_char> {
+    let s = CString::new(s)?;
+    let ptr = s.as_ptr();
+    strings.push(s);
+    Ok(ptr)
+}
+
+unsafe fn build_pure(p: &Pure, strings: &mut Vec<CString>) -> Result<Guard<RzILOpPure>> {
+    let mut b = |p: &Pure| build_pure(p, strings);
+    let op = match p {
+        Pure::Var { name, kind } => {
+            let kind = match kind {
+                VarKind::Global => RzILVarKind_RZ_IL_VAR_KIND_GLOBAL,
+                VarKind::Local => RzILVarKind_RZ_IL_VAR_KIND_LOCAL,
+                VarKind::LocalImmutable => RzILVarKind_RZ_IL_VAR_KIND_LOCAL_IMMUTABLE,
+            };
+            rz_il_op_new_var(intern(strings, name)?, kind)
+        }
+        Pure::Ite { cond, x, y } => {
+            let (c, x, y) = (b(cond)?, b(x)?, b(y)?);
+            rz_il_op_new_ite(c.raw(), x.raw(), y.raw())
+        }
+        Pure::Let { name, exp, body } => {
+            let (exp, body) = (b(exp)?, b(body)?);
+            rz_il_op_new_let(intern(strings, name)?, exp.raw(), body.raw())
+        }
+        Pure::Bool(false) => rz_il_op_new_b0(),
+        Pure::Bool(true) => rz_il_op_new_b1(),
+        Pure::Inv(x) => rz_il_op_new_bool_inv(b(x)?.raw()),
+        Pure::BoolBin { op, x, y } => {
+            let (x, y) = (b(x)?, b(y)?);
+            let (x, y) = (x.raw(), y.raw());
+            match op {
+                BoolOp::And => rz_il_op_new_bool_and(x, y),
+                BoolOp::Or => rz_il_op_new_bool_or(x, y),
+                BoolOp::Xor => rz_il_op_new_bool_xor(x, y),
+            }
+        }
+        Pure::Bitv(bv) => rz_il_op_new_bitv(bv.to_raw()?),
+        Pure::Msb(x) => rz_il_op_new_msb(b(x)?.raw()),
+        Pure::Lsb(x) => rz_il_op_new_lsb(b(x)?.raw()),
+        Pure::IsZero(x) => rz_il_op_new_is_zero(b(x)?.raw()),
+        Pure::Neg(x) => rz_il_op_new_neg(b(x)?.raw()),
+        Pure::LogNot(x) => rz_il_op_new_log_not(b(x)?.raw()),
+        Pure::Binary { op, x, y } => {
+            let (x, y) = (b(x)?, b(y)?);
+            let (x, y) = (x.raw(), y.raw());
+            match op {
+                BinOp::Add => rz_il_op_new_add(x, y),
+                BinOp::Sub => rz_il_op_new_sub(x, y),
+                BinOp::Mul => rz_il_op_new_mul(x, y),
+                BinOp::Div => rz_il_op_new_div(x, y),
+                BinOp::Sdiv => rz_il_op_new_sdiv(x, y),
+                BinOp::Mod => rz_il_op_new_mod(x, y),
+                BinOp::Smod => rz_il_op_new_smod(x, y),
+                BinOp::LogAnd => rz_il_op_new_log_and(x, y),
+                BinOp::LogOr => rz_il_op_new_log_or(x, y),
+                BinOp::LogXor => rz_il_op_new_log_xor(x, y),
+            }
+        }
+        Pure::Shift { dir, fill, x, y } => {
+            let (fill, x, y) = (b(fill)?, b(x)?, b(y)?);
+            match dir {
+                ShiftDir::Left => rz_il_op_new_shiftl(fill.raw(), x.raw(), y.raw()),
+                ShiftDir::Right => rz_il_op_new_shiftr(fill.raw(), x.raw(), y.raw()),
+            }
+        }
+        Pure::Cmp { op, x, y } => {
+            let (x, y) = (b(x)?, b(y)?);
+            let (x, y) = (x.raw(), y.raw());
+            match op {
+                CmpOp::Eq => rz_il_op_new_eq(x, y),
+                CmpOp::Sle => rz_il_op_new_sle(x, y),
+                CmpOp::Ule => rz_il_op_new_ule(x, y),
+            }
+        }
+        Pure::Cast { length, fill, val } => {
+            let (fill, val) = (b(fill)?, b(val)?);
+            rz_il_op_new_cast(*length, fill.raw(), val.raw())
+        }
+        Pure::Append { high, low } => {
+            let (high, low) = (b(high)?, b(low)?);
+            rz_il_op_new_append(high.raw(), low.raw())
+        }
+        Pure::Load { mem, key } => rz_il_op_new_load(*mem, b(key)?.raw()),
+        Pure::LoadW { mem, key, bits } => rz_il_op_new_loadw(*mem, b(key)?.raw(), *bits),
+        Pure::Float { format, bv } => rz_il_op_new_float(*format, b(bv)?.raw()),
+        Pure::FUnary { op, f } => {
+            let f = b(f)?.raw();
+            match op {
+                FUnOp::Fbits => rz_il_op_new_fbits(f),
+                FUnOp::IsFinite => rz_il_op_new_is_finite(f),
+                FUnOp::IsNan => rz_il_op_new_is_nan(f),
+                FUnOp::IsInf => rz_il_op_new_is_inf(f),
+                FUnOp::IsFzero => rz_il_op_new_is_fzero(f),
+                FUnOp::IsFneg => rz_il_op_new_is_fneg(f),
+                FUnOp::IsFpos => rz_il_op_new_is_fpos(f),
+                FUnOp::Fneg => rz_il_op_new_fneg(f),
+                FUnOp::Fabs => rz_il_op_new_fabs(f),
+                FUnOp::Fsucc => rz_il_op_new_fsucc(f),
+                FUnOp::Fpred => rz_il_op_new_fpred(f),
+            }
+        }
+        Pure::FBinary { op, mode, x, y } => {
+            let (x, y) = (b(x)?, b(y)?);
+            let (x, y) = (x.raw(), y.raw());
+            match op {
+                FBinOp::Fadd => rz_il_op_new_fadd(*mode, x, y),
+                FBinOp::Fsub => rz_il_op_new_fsub(*mode, x, y),
+                FBinOp::Fmul => rz_il_op_new_fmul(*mode, x, y),
+                FBinOp::Fdiv => rz_il_op_new_fdiv(*mode, x, y),
+                FBinOp::Fmod => rz_il_op_new_fmod(*mode, x, y),
+            }
+        }
+        Pure::Forder { x, y } => {
+            let (x, y) = (b(x)?, b(y)?);
+            rz_il_op_new_forder(x.raw(), y.raw())
+        }
+        Pure::Fround { mode, f } => rz_il_op_new_fround(*mode, b(f)?.raw()),
+        Pure::Fsqrt { mode, f } => rz_il_op_new_fsqrt(*mode, b(f)?.raw()),
+        Pure::FcastInt {
+            signed,
+            length,
+            mode,
+            f,
+        } => {
+            let f = b(f)?.raw();
+            if *signed {
+                rz_il_op_new_fcast_sint(*length, *mode, f)
+            } else {
+                rz_il_op_new_fcast_int(*length, *mode, f)
+            }
+        }
+        Pure::FcastFloat {
+            signed,
+            format,
+            mode,
+            bv,
+        } => {
+            let bv = b(bv)?.raw();
+            if *signed {
+                rz_il_op_new_fcast_sfloat(*format, *mode, bv)
+            } else {
+                rz_il_op_new_fcast_float(*format, *mode, bv)
+            }
+        }
+        Pure::Fconvert { format, mode, f } => rz_il_op_new_fconvert(*format, *mode, b(f)?.raw()),
+        Pure::Unsupported(code) => {
+            return Err(RizinError::Unsupported(format!(
+                "unsupported il pure code {}",
+                code
+            )))
+        }
+    };
+    Guard::new(op)
+}
+
+unsafe fn build_effect(e: &Effect, strings: &mut Vec<CString>) -> Result<Guard<RzILOpEffect>> {
+    let op = match e {
+        Effect::Empty => rz_il_op_new_empty(),
+        Effect::Nop => rz_il_op_new_nop(),
+        Effect::Set { var, is_local, x } => {
+            let x = build_pure(x, strings)?;
+            rz_il_op_new_set(intern(strings, var)?, *is_local, x.raw())
+        }
+        Effect::Jmp(dst) => rz_il_op_new_jmp(build_pure(dst, strings)?.raw()),
+        Effect::Goto(lbl) => rz_il_op_new_goto(intern(strings, lbl)?),
+        Effect::Seq(x, y) => {
+            let (x, y) = (build_effect(x, strings)?, build_effect(y, strings)?);
+            rz_il_op_new_seq(x.raw(), y.raw())
+        }
+        Effect::Blk { label, data, ctrl } => {
+            let (data, ctrl) = (build_effect(data, strings)?, build_effect(ctrl, strings)?);
+            let label = match label {
+                Some(l) => intern(strings, l)?,
+                None => std::ptr::null(),
+            };
+            rz_il_op_new_blk(label, data.raw(), ctrl.raw())
+        }
+        Effect::Repeat { cond, data } => {
+            let (cond, data) = (build_pure(cond, strings)?, build_effect(data, strings)?);
+            rz_il_op_new_repeat(cond.raw(), data.raw())
+        }
+        Effect::Branch { cond, t, f } => {
+            let cond = build_pure(cond, strings)?;
+            let (t, f) = (build_effect(t, strings)?, build_effect(f, strings)?);
+            rz_il_op_new_branch(cond.raw(), t.raw(), f.raw())
+        }
+        Effect::Store { mem, key, value } => {
+            let (key, value) = (build_pure(key, strings)?, build_pure(value, strings)?);
+            rz_il_op_new_store(*mem, key.raw(), value.raw())
+        }
+        Effect::StoreW { mem, key, value } => {
+            let (key, value) = (build_pure(key, strings)?, build_pure(value, strings)?);
+            rz_il_op_new_storew(*mem, key.raw(), value.raw())
+        }
+    };
+    Guard::new(op)
+}
+
+/// Walks an IL tree; override a method and call the matching `walk_*` to keep descending.
+pub trait Visitor {
+    fn visit_effect(&mut self, e: &Effect) {
+        walk_effect(self, e)
+    }
+
+    fn visit_pure(&mut self, p: &Pure) {
+        walk_pure(self, p)
+    }
+}
+
+pub fn walk_effect<V: Visitor + ?Sized>(v: &mut V, e: &Effect) {
+    match e {
+        Effect::Empty | Effect::Nop | Effect::Goto(_) => {}
+        Effect::Set { x, .. } => v.visit_pure(x),
+        Effect::Jmp(dst) => v.visit_pure(dst),
+        Effect::Seq(x, y) => {
+            v.visit_effect(x);
+            v.visit_effect(y);
+        }
+        Effect::Blk { data, ctrl, .. } => {
+            v.visit_effect(data);
+            v.visit_effect(ctrl);
+        }
+        Effect::Repeat { cond, data } => {
+            v.visit_pure(cond);
+            v.visit_effect(data);
+        }
+        Effect::Branch { cond, t, f } => {
+            v.visit_pure(cond);
+            v.visit_effect(t);
+            v.visit_effect(f);
+        }
+        Effect::Store { key, value, .. } | Effect::StoreW { key, value, .. } => {
+            v.visit_pure(key);
+            v.visit_pure(value);
+        }
+    }
+}
+
+pub fn walk_pure<V: Visitor + ?Sized>(v: &mut V, p: &Pure) {
+    match p {
+        Pure::Var { .. } | Pure::Bool(_) | Pure::Bitv(_) | Pure::Unsupported(_) => {}
+        Pure::Inv(x)
+        | Pure::Msb(x)
+        | Pure::Lsb(x)
+        | Pure::IsZero(x)
+        | Pure::Neg(x)
+        | Pure::LogNot(x)
+        | Pure::Load { key: x, .. }
+        | Pure::LoadW { key: x, .. }
+        | Pure::Float { bv: x, .. }
+        | Pure::FUnary { f: x, .. }
+        | Pure::Fround { f: x, .. }
+        | Pure::Fsqrt { f: x, .. }
+        | Pure::FcastInt { f: x, .. }
+        | Pure::FcastFloat { bv: x, .. }
+        | Pure::Fconvert { f: x, .. } => v.visit_pure(x),
+        Pure::BoolBin { x, y, .. }
+        | Pure::Binary { x, y, .. }
+        | Pure::Cmp { x, y, .. }
+        | Pure::FBinary { x, y, .. }
+        | Pure::Forder { x, y }
+        | Pure::Append { high: x, low: y }
+        | Pure::Cast {
+            fill: x, val: y, ..
+        }
+        | Pure::Let {
+            exp: x, body: y, ..
+        } => {
+            v.visit_pure(x);
+            v.visit_pure(y);
+        }
+        Pure::Ite { cond, x, y } => {
+            v.visit_pure(cond);
+            v.visit_pure(x);
+            v.visit_pure(y);
+        }
+        Pure::Shift { fill, x, y, .. } => {
+            v.visit_pure(fill);
+            v.visit_pure(x);
+            v.visit_pure(y);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrapper::*;
+
+    #[derive(Default)]
+    struct Vars(Vec<String>);
+
+    impl Visitor for Vars {
+        fn visit_effect(&mut self, e: &Effect) {
+            if let Effect::Set { var, .. } = e {
+                self.0.push(var.clone());
+            }
+            walk_effect(self, e)
+        }
+
+        fn visit_pure(&mut self, p: &Pure) {
+            if let Pure::Var { name, .. } = p {
+                self.0.push(name.clone());
+            }
+            walk_pure(self, p)
+        }
+    }
+
+    #[test]
+    fn test_il_round_trip() {
+        let eff = Effect::Seq(
+            Box::new(Effect::Set {
+                var: "r0".to_owned(),
+                is_local: false,
+                x: Pure::Binary {
+                    op: BinOp::Add,
+                    x: Box::new(Pure::Var {
+                        name: "r1".to_owned(),
+                        kind: VarKind::Global,
+                    }),
+                    y: Box::new(Pure::Bitv(BitVector::from_u64(32, 4))),
+                },
+            }),
+            Box::new(Effect::Jmp(Pure::Bitv(BitVector::from_u64(32, 0x100)))),
+        );
+        let raw = eff.to_raw().unwrap();
+        assert_eq!(raw.to_effect().unwrap(), eff);
+        assert!(raw.to_string().contains("r1"));
+
+        let mut vars = Vars::default();
+        vars.visit_effect(&eff);
+        assert_eq!(vars.0, ["r0", "r1"]);
+    }
+
+    #[test]
+    fn test_analysis_op_il() {
+        let core = Core::new();
+        core.set("analysis.arch", "x86").unwrap();
+        core.set("analysis.bits", "64").unwrap();
+        // mov eax, 1
+        let op = core
+            .analysis_op(&[0xb8, 0x01, 0x00, 0x00, 0x00], 0)
+            .unwrap();
+        let il = op.il().unwrap();
+        let mut vars = Vars::default();
+        vars.visit_effect(&il);
+        assert!(vars.0.iter().any(|v| v == "rax"));
+    }
+}
@@ -0,0 +1,399 @@
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::marker::PhantomData;
+use std::ptr::{null_mut, NonNull};
+use std::slice;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::wrapper::{cstr_to_string, Core, PVectorRef, Perm, Result, RizinError};
+use crate::*;
+
+/// A data source behind a custom URI scheme, see [`Io::register_plugin`].
+///
+/// Backends live in the `RzIO` of their [`Core`], which can be sent to another
+/// thread, hence the `Send` bound.
+pub trait IoBackend: Send {
+    /// Reads into `buf` at `offset`, returning the number of bytes read.
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> usize;
+
+    /// Writes `buf` at `offset`, returning the number of bytes written.
+    fn write_at(&mut self, _offset: u64, _buf: &[u8]) -> usize {
+        0
+    }
+
+    fn size(&self) -> u64;
+}
+
+type IoFactory = Arc<dyn Fn(&str) -> Option<Box<dyn IoBackend + Send>> + Send + Sync>;
+
+/// Schemes served by the Rust IO plugin, shared by every [`Io`].
+fn schemes() -> &'static Mutex<Vec<(String, IoFactory)>> {
+    static SCHEMES: OnceLock<Mutex<Vec<(String, IoFactory)>>> = OnceLock::new();
+    SCHEMES.get_or_init(Default::default)
+}
+
+struct Handle {
+    backend: Box<dyn IoBackend + Send>,
+    pos: u64,
+}
+
+unsafe fn handle<'a>(desc: *mut RzIODesc) -> Option<&'a mut Handle> {
+    desc.as_ref().and_then(|d| (d.data as *mut Handle).as_mut())
+}
+
+unsafe extern "C" fn plugin_check(_io: *mut RzIO, pathname: *const c_char, _many: bool) -> bool {
+    let Ok(path) = CStr::from_ptr(pathname).to_str() else {
+        return false;
+    };
+    let schemes = schemes().lock().unwrap();
+    schemes.iter().any(|(s, _)| path.starts_with(s.as_str()))
+}
+
+unsafe extern "C" fn plugin_open(
+    io: *mut RzIO,
+    pathname: *const c_char,
+    perm: c_int,
+    mode: c_int,
+) -> *mut RzIODesc {
+    let Ok(path) = CStr::from_ptr(pathname).to_str() else {
+        return null_mut();
+    };
+    // The factory runs unlocked, it may open or register IO itself.
+    let factory = {
+        let schemes = schemes().lock().unwrap();
+        schemes
+            .iter()
+            .find(|(s, _)| path.starts_with(s.as_str()))
+            .map(|(_, factory)| factory.clone())
+    };
+    let Some(backend) = factory.and_then(|factory| factory(path)) else {
+        return null_mut();
+    };
+    let data = Box::into_raw(Box::new(Handle { backend, pos: 0 }));
+    let desc = rz_io_desc_new(io, plugin(), pathname, perm, mode, data as _);
+    if desc.is_null() {
+        drop(Box::from_raw(data));
+    }
+    desc
+}
+
+unsafe extern "C" fn plugin_close(desc: *mut RzIODesc) -> c_int {
+    if let Some(d) = desc.as_mut() {
+        if !d.data.is_null() {
+            drop(Box::from_raw(d.data as *mut Handle));
+            d.data = null_mut();
+        }
+    }
+    0
+}
+
+unsafe extern "C" fn plugin_read(
+    _io: *mut RzIO,
+    desc: *mut RzIODesc,
+    buf: *mut u8,
+    count: c_int,
+) -> c_int {
+    let Some(h) = handle(desc) else {
+        return -1;
+    };
+    let n = h
+        .backend
+        .read_at(h.pos, slice::from_raw_parts_mut(buf, count.max(0) as _));
+    h.pos += n as u64;
+    n as _
+}
+
+unsafe extern "C" fn plugin_write(
+    _io: *mut RzIO,
+    desc: *mut RzIODesc,
+    buf: *const u8,
+    count: c_int,
+) -> c_int {
+    let Some(h) = handle(desc) else {
+        return -1;
+    };
+    let n = h
+        .backend
+        .write_at(h.pos, slice::from_raw_parts(buf, count.max(0) as _));
+    h.pos += n as u64;
+    n as _
+}
+
+unsafe extern "C" fn plugin_lseek(
+    _io: *mut RzIO,
+    desc: *mut RzIODesc,
+    offset: u64,
+    whence: c_int,
+) -> u64 {
+    let Some(h) = handle(desc) else {
+        return u64::MAX;
+    };
+    h.pos = match whence as u32 {
+        RZ_IO_SEEK_SET => offset,
+        RZ_IO_SEEK_CUR => h.pos.wrapping_add(offset),
+        RZ_IO_SEEK_END => h.backend.size().wrapping_add(offset),
+        _ => h.pos,
+    };
+    h.pos
+}
+
+struct PluginPtr(*mut RzIOPlugin);
+unsafe impl Send for PluginPtr {}
+unsafe impl Sync for PluginPtr {}
+
+/// The single `RzIOPlugin` dispatching to every registered [`IoBackend`] scheme.
+fn plugin() -> *mut RzIOPlugin {
+    static PLUGIN: OnceLock<PluginPtr> = OnceLock::new();
+    PLUGIN
+        .get_or_init(|| {
+            let plugin = RzIOPlugin {
+                name: c"rust".as_ptr(),
+                desc: c"IO backed by Rust implementations of IoBackend".as_ptr(),
+                license: c"LGPL3".as_ptr(),
+                check: Some(plugin_check),
+                open: Some(plugin_open),
+                close: Some(plugin_close),
+                read: Some(plugin_read),
+                write: Some(plugin_write),
+                lseek: Some(plugin_lseek),
+                ..Default::default()
+            };
+            PluginPtr(Box::into_raw(Box::new(plugin)))
+        })
+        .0
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IoMap {
+    pub id: u32,
+    pub fd: i32,
+    pub perm: Perm,
+    pub addr: u64,
+    pub size: u64,
+    pub delta: u64,
+    pub name: Option<String>,
+}
+
+/// An `RzIO` instance, either standalone or borrowed from a [`Core`].
+pub struct Io<'a> {
+    pub inner: NonNull<RzIO>,
+    owned: bool,
+    marker: PhantomData<&'a RzCore>,
+}
+
+impl Io<'static> {
+    pub fn new() -> Self {
+        Self {
+            inner: NonNull::new(unsafe { rz_io_new() }).unwrap(),
+            owned: true,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl Default for Io<'static> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Io<'_> {
+    fn drop(&mut self) {
+        if self.owned {
+            unsafe {
+                rz_io_free(self.inner.as_ptr());
+            }
+        }
+    }
+}
+
+impl Core {
+    pub fn io(&self) -> Io<'_> {
+        Io {
+            inner: NonNull::new(unsafe { self.0.as_ref().io }).unwrap(),
+            owned: false,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl Io<'_> {
+    /// Opens `uri` and maps it at address 0, returning the file descriptor.
+    pub fn open(&self, uri: &str, perm: Perm) -> Result<i32> {
+        let curi = CString::new(uri)?;
+        let desc =
+            unsafe { rz_io_open(self.inner.as_ptr(), curi.as_ptr(), perm.bits() as _, 0o644) };
+        unsafe { desc.as_ref() }
+            .map(|d| d.fd)
+            .ok_or(RizinError::OpenFailed {
+                path: uri.to_owned(),
+            })
+    }
+
+    /// Opens `uri` without mapping it.
+    pub fn open_nomap(&self, uri: &str, perm: Perm) -> Result<i32> {
+        let curi = CString::new(uri)?;
+        let desc = unsafe {
+            rz_io_open_nomap(self.inner.as_ptr(), curi.as_ptr(), perm.bits() as _, 0o644)
+        };
+        unsafe { desc.as_ref() }
+            .map(|d| d.fd)
+            .ok_or(RizinError::OpenFailed {
+                path: uri.to_owned(),
+            })
+    }
+
+    pub fn close(&self, fd: i32) -> Result<()> {
+        if unsafe { rz_io_fd_close(self.inner.as_ptr(), fd) } {
+            Ok(())
+        } else {
+            Err(RizinError::Fd { fd })
+        }
+    }
+
+    /// Maps `size` bytes of `fd` starting at `delta` to `addr`, returning the map id.
+    pub fn map_add(&self, fd: i32, perm: Perm, delta: u64, addr: u64, size: u64) -> Result<u32> {
+        let map =
+            unsafe { rz_io_map_add(self.inner.as_ptr(), fd, perm.bits() as _, delta, addr, size) };
+        unsafe { map.as_ref() }
+            .map(|m| m.id)
+            .ok_or(RizinError::Fd { fd })
+    }
+
+    pub fn map_del(&self, id: u32) -> bool {
+        unsafe { rz_io_map_del(self.inner.as_ptr(), id) }
+    }
+
+    pub fn maps(&self) -> Vec<IoMap> {
+        let maps = unsafe { rz_io_maps(self.inner.as_ptr()) };
+        // The maps are owned by the io.
+        let Some(maps) = (unsafe { PVectorRef::<RzIOMap>::from_raw(maps) }) else {
+            return vec![];
+        };
+        maps.iter()
+            .map(|m| IoMap {
+                id: m.id,
+                fd: m.fd,
+                perm: Perm::from_bits_truncate(m.perm as _),
+                addr: m.itv.addr,
+                size: m.itv.size,
+                delta: m.delta,
+                name: unsafe { cstr_to_string(m.name) },
+            })
+            .collect()
+    }
+
+    /// Reads through the maps, failing if any byte of the range is unmapped.
+    pub fn read_at(&self, addr: u64, buf: &mut [u8]) -> Result<()> {
+        if unsafe { rz_io_read_at(self.inner.as_ptr(), addr, buf.as_mut_ptr(), buf.len() as _) } {
+            Ok(())
+        } else {
+            Err(RizinError::Io { addr })
+        }
+    }
+
+    pub fn write_at(&self, addr: u64, buf: &[u8]) -> Result<()> {
+        if unsafe { rz_io_write_at(self.inner.as_ptr(), addr, buf.as_ptr(), buf.len() as _) } {
+            Ok(())
+        } else {
+            Err(RizinError::Io { addr })
+        }
+    }
+
+    /// Serves URIs starting with `scheme` (e.g. `"blob://"`) from backends built by `factory`,
+    /// replacing the factory previously registered for `scheme`.
+    /// Schemes are process-wide, the plugin is added to this io on demand.
+    pub fn register_plugin<F>(&self, scheme: &str, factory: F)
+    where
+        F: Fn(&str) -> Option<Box<dyn IoBackend + Send>> + Send + Sync + 'static,
+    {
+        {
+            let mut schemes = schemes().lock().unwrap();
+            schemes.retain(|(s, _)| s != scheme);
+            schemes.push((scheme.to_owned(), Arc::new(factory)));
+        }
+        unsafe {
+            let name = c"rust".as_ptr();
+            if rz_io_plugin_byname(self.inner.as_ptr(), name).is_null() {
+                rz_io_plugin_add(self.inner.as_ptr(), plugin());
+            }
+        }
+    }
+
+    /// Stops serving `scheme`, returns whether it was registered. Descriptors
+    /// already open keep their backend.
+    pub fn unregister_plugin(&self, scheme: &str) -> bool {
+        let mut schemes = schemes().lock().unwrap();
+        let len = schemes.len();
+        schemes.retain(|(s, _)| s != scheme);
+        schemes.len() != len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrapper::*;
+
+    struct Pattern(u64);
+
+    impl IoBackend for Pattern {
+        fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> usize {
+            let n = buf.len().min(self.0.saturating_sub(offset) as usize);
+            for (i, b) in buf[..n].iter_mut().enumerate() {
+                *b = (offset as usize + i) as u8;
+            }
+            n
+        }
+
+        fn size(&self) -> u64 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_io_malloc() {
+        let io = Io::new();
+        let fd = io.open("malloc://0x100", Perm::R | Perm::W).unwrap();
+        io.map_add(fd, Perm::R | Perm::W, 0, 0x1000, 0x100).unwrap();
+        io.write_at(0x1010, &[1, 2, 3, 4]).unwrap();
+        let mut buf = [0u8; 4];
+        io.read_at(0x1010, &mut buf).unwrap();
+        assert_eq!(buf, [1, 2, 3, 4]);
+        assert!(io
+            .maps()
+            .iter()
+            .any(|m| m.addr == 0x1000 && m.size == 0x100));
+    }
+
+    #[test]
+    fn test_io_plugin() {
+        let io = Io::new();
+        io.register_plugin("pattern://", |_| Some(Box::new(Pattern(0x40))));
+        io.open("pattern://x", Perm::R).unwrap();
+        let mut buf = [0u8; 4];
+        io.read_at(0x10, &mut buf).unwrap();
+        assert_eq!(buf, [0x10, 0x11, 0x12, 0x13]);
+    }
+
+    #[test]
+    fn test_io_plugin_replace() {
+        let io = Io::new();
+        io.register_plugin("short://", |_| Some(Box::new(Pattern(0x10))));
+        io.register_plugin("short://", |_| Some(Box::new(Pattern(0x40))));
+        io.open("short://x", Perm::R).unwrap();
+        let mut buf = [0u8; 4];
+        io.read_at(0x20, &mut buf).unwrap();
+        assert_eq!(buf, [0x20, 0x21, 0x22, 0x23]);
+
+        // A factory may use the IO itself.
+        io.register_plugin("nested://", |_| {
+            let io = Io::new();
+            io.register_plugin("inner://", |_| None);
+            Some(Box::new(Pattern(4)))
+        });
+        io.open("nested://x", Perm::R).unwrap();
+        assert!(io.unregister_plugin("inner://"));
+        assert!(io.unregister_plugin("short://"));
+        assert!(!io.unregister_plugin("short://"));
+        assert!(io.open("short://y", Perm::R).is_err());
+    }
+}
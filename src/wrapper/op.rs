@@ -0,0 +1,333 @@
+use std::ffi::CStr;
+
+use bitflags::bitflags;
+
+use crate::wrapper::{cstr_to_string, AnalysisOp};
+use crate::*;
+
+bitflags! {
+    /// Which parts of an `RzAnalysisOp` get filled by [`Core::analysis_op_with`](crate::wrapper::Core::analysis_op_with).
+    /// The empty mask (`RZ_ANALYSIS_OP_MASK_BASIC`) decodes only size, type and targets.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+    pub struct OpMask: u32 {
+        const ESIL = RzAnalysisOpMask_RZ_ANALYSIS_OP_MASK_ESIL;
+        const VAL = RzAnalysisOpMask_RZ_ANALYSIS_OP_MASK_VAL;
+        const HINT = RzAnalysisOpMask_RZ_ANALYSIS_OP_MASK_HINT;
+        const OPEX = RzAnalysisOpMask_RZ_ANALYSIS_OP_MASK_OPEX;
+        const DISASM = RzAnalysisOpMask_RZ_ANALYSIS_OP_MASK_DISASM;
+        const IL = RzAnalysisOpMask_RZ_ANALYSIS_OP_MASK_IL;
+        const ALL = RzAnalysisOpMask_RZ_ANALYSIS_OP_MASK_ALL;
+    }
+}
+
+bitflags! {
+    /// Modifier bits of `RzAnalysisOp.type` on top of its [`OpType`].
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+    pub struct OpTypeFlags: u32 {
+        const COND = _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_COND;
+        const REP = _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_REP;
+        const MEM = _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_MEM;
+        const REG = _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_REG;
+        const IND = _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_IND;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OpType {
+    Null,
+    Jmp,
+    UJmp,
+    Call,
+    UCall,
+    Ret,
+    Ill,
+    Unk,
+    Nop,
+    Mov,
+    Trap,
+    Swi,
+    UPush,
+    Push,
+    Pop,
+    Cmp,
+    Acmp,
+    Add,
+    Sub,
+    Io,
+    Mul,
+    Div,
+    Shr,
+    Shl,
+    Sal,
+    Sar,
+    Or,
+    And,
+    Xor,
+    Nor,
+    Not,
+    Store,
+    Load,
+    Lea,
+    Leave,
+    Ror,
+    Rol,
+    Xchg,
+    Mod,
+    Switch,
+    Case,
+    Length,
+    Cast,
+    New,
+    Abs,
+    Cpl,
+    Crypto,
+    Sync,
+    Other(u32),
+}
+
+impl From<u32> for OpType {
+    fn from(value: u32) -> Self {
+        match value & !OpTypeFlags::all().bits() {
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_NULL => Self::Null,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_JMP => Self::Jmp,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_UJMP => Self::UJmp,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_CALL => Self::Call,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_UCALL => Self::UCall,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_RET => Self::Ret,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_ILL => Self::Ill,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_UNK => Self::Unk,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_NOP => Self::Nop,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_MOV => Self::Mov,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_TRAP => Self::Trap,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_SWI => Self::Swi,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_UPUSH => Self::UPush,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_PUSH => Self::Push,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_POP => Self::Pop,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_CMP => Self::Cmp,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_ACMP => Self::Acmp,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_ADD => Self::Add,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_SUB => Self::Sub,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_IO => Self::Io,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_MUL => Self::Mul,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_DIV => Self::Div,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_SHR => Self::Shr,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_SHL => Self::Shl,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_SAL => Self::Sal,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_SAR => Self::Sar,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_OR => Self::Or,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_AND => Self::And,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_XOR => Self::Xor,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_NOR => Self::Nor,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_NOT => Self::Not,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_STORE => Self::Store,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_LOAD => Self::Load,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_LEA => Self::Lea,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_LEAVE => Self::Leave,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_ROR => Self::Ror,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_ROL => Self::Rol,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_XCHG => Self::Xchg,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_MOD => Self::Mod,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_SWITCH => Self::Switch,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_CASE => Self::Case,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_LENGTH => Self::Length,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_CAST => Self::Cast,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_NEW => Self::New,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_ABS => Self::Abs,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_CPL => Self::Cpl,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_CRYPTO => Self::Crypto,
+            _RzAnalysisOpType_RZ_ANALYSIS_OP_TYPE_SYNC => Self::Sync,
+            x => Self::Other(x),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cond {
+    Al,
+    Eq,
+    Ne,
+    Ge,
+    Gt,
+    Le,
+    Lt,
+    Nv,
+    Hs,
+    Lo,
+    Mi,
+    Pl,
+    Vs,
+    Vc,
+    Hi,
+    Ls,
+    Other(u32),
+}
+
+impl From<RzTypeCond> for Cond {
+    fn from(value: RzTypeCond) -> Self {
+        match value {
+            RzTypeCond_RZ_TYPE_COND_AL => Self::Al,
+            RzTypeCond_RZ_TYPE_COND_EQ => Self::Eq,
+            RzTypeCond_RZ_TYPE_COND_NE => Self::Ne,
+            RzTypeCond_RZ_TYPE_COND_GE => Self::Ge,
+            RzTypeCond_RZ_TYPE_COND_GT => Self::Gt,
+            RzTypeCond_RZ_TYPE_COND_LE => Self::Le,
+            RzTypeCond_RZ_TYPE_COND_LT => Self::Lt,
+            RzTypeCond_RZ_TYPE_COND_NV => Self::Nv,
+            RzTypeCond_RZ_TYPE_COND_HS => Self::Hs,
+            RzTypeCond_RZ_TYPE_COND_LO => Self::Lo,
+            RzTypeCond_RZ_TYPE_COND_MI => Self::Mi,
+            RzTypeCond_RZ_TYPE_COND_PL => Self::Pl,
+            RzTypeCond_RZ_TYPE_COND_VS => Self::Vs,
+            RzTypeCond_RZ_TYPE_COND_VC => Self::Vc,
+            RzTypeCond_RZ_TYPE_COND_HI => Self::Hi,
+            RzTypeCond_RZ_TYPE_COND_LS => Self::Ls,
+            x => Self::Other(x as _),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueKind {
+    Reg,
+    Mem,
+    Imm,
+    Other(u32),
+}
+
+/// A source or destination operand (`RzAnalysisValue`), only filled with [`OpMask::VAL`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Value {
+    pub kind: ValueKind,
+    /// Size of the memory access in bytes, 0 if the value is not a memory reference.
+    pub memref: i32,
+    pub base: u64,
+    pub delta: i64,
+    pub imm: i64,
+    pub mul: i32,
+    pub reg: Option<String>,
+    pub regdelta: Option<String>,
+}
+
+impl Value {
+    unsafe fn from_raw(v: &RzAnalysisValue) -> Self {
+        let reg_name = |r: *mut RzRegItem| r.as_ref().and_then(|r| cstr_to_string(r.name));
+        Self {
+            kind: match v.type_ {
+                RzAnalysisValueType_RZ_ANALYSIS_VAL_REG => ValueKind::Reg,
+                RzAnalysisValueType_RZ_ANALYSIS_VAL_MEM => ValueKind::Mem,
+                RzAnalysisValueType_RZ_ANALYSIS_VAL_IMM => ValueKind::Imm,
+                x => ValueKind::Other(x as _),
+            },
+            memref: v.memref,
+            base: v.base,
+            delta: v.delta,
+            imm: v.imm,
+            mul: v.mul,
+            reg: reg_name(v.reg),
+            regdelta: reg_name(v.regdelta),
+        }
+    }
+}
+
+/// Maps rizin's `UT64_MAX` "no address" marker to `None`.
+pub(crate) fn opt_addr(x: u64) -> Option<u64> {
+    (x != u64::MAX).then_some(x)
+}
+
+impl AnalysisOp {
+    pub fn addr(&self) -> u64 {
+        self.0.addr
+    }
+
+    pub fn size(&self) -> usize {
+        self.0.size as _
+    }
+
+    pub fn op_type(&self) -> OpType {
+        OpType::from(self.0.type_)
+    }
+
+    pub fn op_type_flags(&self) -> OpTypeFlags {
+        OpTypeFlags::from_bits_truncate(self.0.type_)
+    }
+
+    pub fn is_invalid(&self) -> bool {
+        self.op_type() == OpType::Ill
+    }
+
+    pub fn jump(&self) -> Option<u64> {
+        opt_addr(self.0.jump)
+    }
+
+    pub fn fail(&self) -> Option<u64> {
+        opt_addr(self.0.fail)
+    }
+
+    pub fn ptr(&self) -> Option<u64> {
+        opt_addr(self.0.ptr)
+    }
+
+    pub fn val(&self) -> Option<u64> {
+        opt_addr(self.0.val)
+    }
+
+    pub fn stackptr(&self) -> i64 {
+        self.0.stackptr
+    }
+
+    pub fn cond(&self) -> Cond {
+        Cond::from(self.0.cond)
+    }
+
+    pub fn family(&self) -> i32 {
+        self.0.family
+    }
+
+    pub fn family_name(&self) -> Option<&'static str> {
+        unsafe {
+            let name = rz_analysis_op_family_to_string(self.0.family);
+            if name.is_null() {
+                None
+            } else {
+                CStr::from_ptr(name).to_str().ok()
+            }
+        }
+    }
+
+    pub fn delay(&self) -> i32 {
+        self.0.delay
+    }
+
+    pub fn src(&self) -> Vec<Value> {
+        self.0
+            .src
+            .iter()
+            .filter_map(|v| unsafe { v.as_ref() })
+            .map(|v| unsafe { Value::from_raw(v) })
+            .collect()
+    }
+
+    pub fn dst(&self) -> Option<Value> {
+        unsafe { self.0.dst.as_ref().map(|v| Value::from_raw(v)) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrapper::*;
+
+    #[test]
+    fn test_op_fields() {
+        let core = Core::new();
+        core.set("analysis.arch", "x86").unwrap();
+        core.set("analysis.bits", "64").unwrap();
+        // call 0x1005
+        let op = core
+            .analysis_op_with(&[0xe8, 0x00, 0x00, 0x00, 0x00], 0x1000, OpMask::ALL)
+            .unwrap();
+        assert_eq!(op.size(), 5);
+        assert_eq!(op.op_type(), OpType::Call);
+        assert_eq!(op.jump(), Some(0x1005));
+        assert_eq!(op.fail(), Some(0x1005));
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+use std::ffi::{c_char, c_void};
+use std::ptr::{addr_of_mut, null};
+
+use bitflags::bitflags;
+
+use crate::wrapper::{cstr_to_string, Core};
+use crate::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PluginKind {
+    Arch,
+    Bin,
+    Io,
+    Hash,
+    Crypto,
+    Core,
+}
+
+impl PluginKind {
+    pub const ALL: [PluginKind; 6] = [
+        PluginKind::Arch,
+        PluginKind::Bin,
+        PluginKind::Io,
+        PluginKind::Hash,
+        PluginKind::Crypto,
+        PluginKind::Core,
+    ];
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+    pub struct Endian: u32 {
+        const LITTLE = RZ_SYS_ENDIAN_LITTLE;
+        const BIG = RZ_SYS_ENDIAN_BIG;
+    }
+}
+
+/// Metadata of a loaded plugin.
+///
+/// Fields a kind of plugin doesn't have are left empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginInfo {
+    pub kind: PluginKind,
+    pub name: String,
+    pub desc: Option<String>,
+    pub license: Option<String>,
+    /// Supported bit widths, e.g. `[16, 32, 64]`.
+    pub bits: Vec<u32>,
+    pub endian: Endian,
+    pub cpus: Vec<String>,
+    pub has_il: bool,
+}
+
+impl PluginInfo {
+    fn new(kind: PluginKind, name: *const c_char) -> Self {
+        Self {
+            kind,
+            name: unsafe { cstr_to_string(name) }.unwrap_or_default(),
+            desc: None,
+            license: None,
+            bits: vec![],
+            endian: Endian::empty(),
+            cpus: vec![],
+            has_il: false,
+        }
+    }
+
+    unsafe fn describe(mut self, desc: *const c_char, license: *const c_char) -> Self {
+        self.desc = cstr_to_string(desc);
+        self.license = cstr_to_string(license);
+        self
+    }
+}
+
+/// Splits a plugin's `bits` mask such as `16 | 32 | 64`.
+fn bits_of(mask: i32) -> Vec<u32> {
+    [4, 8, 16, 32, 64]
+        .into_iter()
+        .filter(|b| mask as u32 & b != 0)
+        .collect()
+}
+
+fn cpus_of(cpus: *const c_char) -> Vec<String> {
+    unsafe { cstr_to_string(cpus) }
+        .map(|s| {
+            s.split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_owned)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+unsafe extern "C" fn collect_value(user: *mut c_void, _k: *const c_char, v: *const c_void) -> bool {
+    let values = &mut *(user as *mut Vec<*const c_void>);
+    values.push(v);
+    true
+}
+
+/// Values of a plugin table, borrowed from `owner`, the rizin object the
+/// table belongs to.
+pub(crate) unsafe fn ht_values<'a, T, O: ?Sized>(ht: *mut HtSP, _owner: &'a O) -> Vec<&'a T> {
+    let mut values: Vec<*const c_void> = vec![];
+    if !ht.is_null() {
+        ht_sp_foreach(ht, Some(collect_value), addr_of_mut!(values) as _);
+    }
+    values
+        .into_iter()
+        .filter_map(|v| (v as *const T).as_ref())
+        .collect()
+}
+
+impl Core {
+    /// Lists the plugins of `kind` loaded by this core, sorted by name.
+    pub fn plugins(&self, kind: PluginKind) -> Vec<PluginInfo> {
+        let mut res = unsafe {
+            let core = self.0.as_ref();
+            match kind {
+                PluginKind::Arch => arch_plugins(core),
+                PluginKind::Bin => ht_values::<RzBinPlugin, _>((*core.bin).plugins, core)
+                    .into_iter()
+                    .map(|p| PluginInfo::new(kind, p.name).describe(p.desc, p.license))
+                    .collect(),
+                PluginKind::Io => ht_values::<RzIOPlugin, _>((*core.io).plugins, core)
+                    .into_iter()
+                    .map(|p| PluginInfo::new(kind, p.name).describe(p.desc, p.license))
+                    .collect(),
+                PluginKind::Hash => ht_values::<RzHashPlugin, _>((*core.hash).plugins, core)
+                    .into_iter()
+                    .map(|p| PluginInfo::new(kind, p.name).describe(null(), p.license))
+                    .collect(),
+                PluginKind::Crypto => crypto_plugins(),
+                PluginKind::Core => ht_values::<RzCorePlugin, _>(core.plugins, core)
+                    .into_iter()
+                    .map(|p| PluginInfo::new(kind, p.name).describe(p.desc, p.license))
+                    .collect(),
+            }
+        };
+        res.sort_by(|a, b| a.name.cmp(&b.name));
+        res
+    }
+
+    /// Arch plugins that lift to RzIL, the ones usable with [`Core::il_vm`].
+    pub fn il_archs(&self) -> Vec<PluginInfo> {
+        self.plugins(PluginKind::Arch)
+            .into_iter()
+            .filter(|p| p.has_il)
+            .collect()
+    }
+}
+
+/// Joins the analysis plugins with the asm plugins of the same name, which
+/// carry the cpu and endian information.
+unsafe fn arch_plugins(core: &RzCore) -> Vec<PluginInfo> {
+    let asm = ht_values::<RzAsmPlugin, _>((*core.rasm).plugins, core)
+        .into_iter()
+        .filter_map(|p| cstr_to_string(p.name).map(|n| (n, p)))
+        .collect::<HashMap<_, _>>();
+    ht_values::<RzAnalysisPlugin, _>((*core.analysis).plugins, core)
+        .into_iter()
+        .map(|p| {
+            let mut info = PluginInfo::new(PluginKind::Arch, p.name).describe(p.desc, p.license);
+            info.bits = bits_of(p.bits);
+            info.has_il = p.il_config.is_some();
+            if let Some(a) = asm.get(&info.name) {
+                info.cpus = cpus_of(a.cpus);
+                info.endian = Endian::from_bits_truncate(a.endian as u32);
+                if info.bits.is_empty() {
+                    info.bits = bits_of(a.bits);
+                }
+            }
+            info
+        })
+        .collect()
+}
+
+unsafe fn crypto_plugins() -> Vec<PluginInfo> {
+    let crypto = rz_crypto_new();
+    if crypto.is_null() {
+        return vec![];
+    }
+    let res = ht_values::<RzCryptoPlugin, _>((*crypto).plugins, &*crypto)
+        .into_iter()
+        .map(|p| PluginInfo::new(PluginKind::Crypto, p.name).describe(null(), p.license))
+        .collect();
+    rz_crypto_free(crypto);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrapper::*;
+
+    #[test]
+    fn test_plugins() {
+        let core = Core::new();
+        let archs = core.plugins(PluginKind::Arch);
+        let x86 = archs.iter().find(|p| p.name == "x86").unwrap();
+        assert!(x86.has_il);
+        assert!(x86.bits.contains(&64));
+        assert!(x86.endian.contains(Endian::LITTLE));
+        assert!(core.il_archs().iter().any(|p| p.name == "x86"));
+        assert!(core
+            .plugins(PluginKind::Bin)
+            .iter()
+            .any(|p| p.name == "elf64"));
+        for kind in PluginKind::ALL {
+            assert!(!core.plugins(kind).is_empty(), "{:?}", kind);
+        }
+    }
+}
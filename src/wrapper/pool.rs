@@ -0,0 +1,135 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use crate::wrapper::{Core, Result};
+
+/// Config keys copied from the template core, in the order they are set.
+const POOL_CONFIG: &[&str] = &[
+    "asm.arch",
+    "asm.cpu",
+    "asm.bits",
+    "analysis.arch",
+    "analysis.cpu",
+    "analysis.bits",
+    "cfg.bigendian",
+];
+
+/// Independent cores sharing the arch, cpu, bits and endianness of a
+/// template, for decoding on several threads.
+///
+/// A [`Core`] is not `Sync`, each pool thread gets a core of its own.
+pub struct CorePool {
+    cores: Vec<Core>,
+}
+
+impl CorePool {
+    /// Creates `n` cores configured like `template`. Use
+    /// [`std::thread::available_parallelism`] for one core per CPU.
+    pub fn new(template: &Core, n: usize) -> Result<Self> {
+        let mut config = vec![];
+        for &k in POOL_CONFIG {
+            config.push((k, template.get(k)?));
+        }
+        let mut cores = vec![];
+        for _ in 0..n.max(1) {
+            let core = Core::new();
+            for (k, v) in &config {
+                core.set(k, v)?;
+            }
+            cores.push(core);
+        }
+        Ok(Self { cores })
+    }
+
+    pub fn len(&self) -> usize {
+        self.cores.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cores.is_empty()
+    }
+
+    pub fn cores(&mut self) -> &mut [Core] {
+        &mut self.cores
+    }
+
+    /// Maps `f` over `items` on one thread per core, results in input order.
+    ///
+    /// `f` only gets a shared core, which is enough to decode but not to run
+    /// [`Core::cmd`] or seek with [`Core::at`]: both take `&mut Core`, and
+    /// commands go through rizin's process wide console under a lock.
+    pub fn par_map<T, R, F>(&mut self, items: &[T], f: F) -> Vec<R>
+    where
+        T: Sync,
+        R: Send,
+        F: Fn(&Core, &T) -> R + Sync,
+    {
+        let next = AtomicUsize::new(0);
+        let mut res = thread::scope(|s| {
+            let workers = self
+                .cores
+                .iter_mut()
+                .map(|core| {
+                    let (next, f) = (&next, &f);
+                    s.spawn(move || {
+                        let core = &*core;
+                        let mut done = vec![];
+                        loop {
+                            let i = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(item) = items.get(i) else {
+                                break done;
+                            };
+                            done.push((i, f(core, item)));
+                        }
+                    })
+                })
+                .collect::<Vec<_>>();
+            workers
+                .into_iter()
+                .flat_map(|w| w.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        res.sort_unstable_by_key(|(i, _)| *i);
+        res.into_iter().map(|(_, r)| r).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrapper::*;
+
+    #[test]
+    fn test_pool() {
+        let core = Core::new();
+        core.set("analysis.arch", "x86").unwrap();
+        core.set("analysis.bits", "32").unwrap();
+        let mut pool = CorePool::new(&core, 4).unwrap();
+        assert_eq!(pool.len(), 4);
+        assert_eq!(pool.cores()[3].get("analysis.bits").unwrap(), "32");
+
+        // nop; ret; push ebp; inc eax
+        let bufs = [[0x90u8], [0xc3], [0x55], [0x40]].repeat(64);
+        let sizes = pool.par_map(&bufs, |core, buf| {
+            let op = core.analysis_op(buf, 0).unwrap();
+            (op.size(), op.mnemonic().unwrap().to_owned())
+        });
+        assert_eq!(sizes.len(), bufs.len());
+        assert_eq!(sizes[1], (1, "ret".to_owned()));
+        assert_eq!(sizes[6], (1, "push ebp".to_owned()));
+    }
+
+    #[test]
+    fn test_pool_cmd() {
+        let mut pool = CorePool::new(&Core::new(), 4).unwrap();
+        // Each thread owns its core, the console they print to is shared.
+        std::thread::scope(|s| {
+            for (i, core) in pool.cores().iter_mut().enumerate() {
+                s.spawn(move || {
+                    for _ in 0..64 {
+                        assert_eq!(core.cmd(&format!("?e {}", i)).unwrap(), format!("{}\n", i));
+                    }
+                });
+            }
+        });
+    }
+}
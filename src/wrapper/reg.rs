@@ -0,0 +1,361 @@
+use std::ffi::CString;
+use std::marker::PhantomData;
+use std::ptr::{addr_of_mut, NonNull};
+
+use crate::wrapper::{cstr_to_string, BitVector, Core, ListRef, Result, RizinError};
+use crate::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegType {
+    Gpr,
+    Drx,
+    Fpu,
+    Mmx,
+    Xmm,
+    Ymm,
+    Flg,
+    Seg,
+    Sys,
+    Sec,
+    Vc,
+    Vcc,
+    Ctr,
+    Other(u32),
+}
+
+impl RegType {
+    fn from_raw(t: u32) -> Self {
+        match t {
+            RzRegisterType_RZ_REG_TYPE_GPR => Self::Gpr,
+            RzRegisterType_RZ_REG_TYPE_DRX => Self::Drx,
+            RzRegisterType_RZ_REG_TYPE_FPU => Self::Fpu,
+            RzRegisterType_RZ_REG_TYPE_MMX => Self::Mmx,
+            RzRegisterType_RZ_REG_TYPE_XMM => Self::Xmm,
+            RzRegisterType_RZ_REG_TYPE_YMM => Self::Ymm,
+            RzRegisterType_RZ_REG_TYPE_FLG => Self::Flg,
+            RzRegisterType_RZ_REG_TYPE_SEG => Self::Seg,
+            RzRegisterType_RZ_REG_TYPE_SYS => Self::Sys,
+            RzRegisterType_RZ_REG_TYPE_SEC => Self::Sec,
+            RzRegisterType_RZ_REG_TYPE_VC => Self::Vc,
+            RzRegisterType_RZ_REG_TYPE_VCC => Self::Vcc,
+            RzRegisterType_RZ_REG_TYPE_CTR => Self::Ctr,
+            x => Self::Other(x),
+        }
+    }
+
+    pub fn is_vector(&self) -> bool {
+        matches!(
+            self,
+            Self::Mmx | Self::Xmm | Self::Ymm | Self::Vc | Self::Vcc
+        )
+    }
+}
+
+/// A register of the profile.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Register {
+    pub name: String,
+    pub reg_type: RegType,
+    /// Index of the arena holding the register.
+    pub arena: usize,
+    /// Size in bits.
+    pub size: u32,
+    /// Offset into the arena in bits.
+    pub offset: u32,
+    pub is_float: bool,
+}
+
+impl Register {
+    unsafe fn from_raw(item: &RzRegItem) -> Self {
+        Self {
+            name: cstr_to_string(item.name).unwrap_or_default(),
+            reg_type: RegType::from_raw(item.type_ as _),
+            arena: item.arena as _,
+            size: item.size as _,
+            offset: item.offset as _,
+            is_float: item.is_float,
+        }
+    }
+
+    /// Whether `self` is a strict part of `other`, e.g. `eax` of `rax`.
+    pub fn is_subregister_of(&self, other: &Register) -> bool {
+        self.arena == other.arena
+            && self.size < other.size
+            && self.offset >= other.offset
+            && self.offset + self.size <= other.offset + other.size
+    }
+}
+
+/// An entry of the profile's `regset`, one per register type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegSet {
+    pub reg_type: RegType,
+    /// The registers of this type, empty if the profile defines none.
+    pub registers: Vec<Register>,
+    /// Size in bytes of the arena backing the set, `None` without one.
+    pub arena_size: Option<usize>,
+}
+
+/// The register profile of a [`Core`]'s analysis.
+pub struct Registers<'a> {
+    pub reg: NonNull<RzReg>,
+    marker: PhantomData<&'a Core>,
+}
+
+impl Core {
+    pub fn registers(&self) -> Registers<'_> {
+        let reg = unsafe { (*self.0.as_ref().analysis).reg };
+        Registers {
+            reg: NonNull::new(reg).unwrap(),
+            marker: PhantomData,
+        }
+    }
+}
+
+/// The state of all register arenas at one point, see [`Registers::snapshot`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisterFile {
+    regs: Vec<Register>,
+    arenas: Vec<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegChange {
+    pub name: String,
+    pub old: BitVector,
+    pub new: BitVector,
+}
+
+impl RegisterFile {
+    pub fn registers(&self) -> &[Register] {
+        &self.regs
+    }
+
+    pub fn value(&self, name: &str) -> Option<BitVector> {
+        let reg = self.regs.iter().find(|r| r.name == name)?;
+        self.value_of(reg)
+    }
+
+    fn value_of(&self, reg: &Register) -> Option<BitVector> {
+        let arena = self.arenas.get(reg.arena)?;
+        let mut bytes = vec![0u8; (reg.size as usize).div_ceil(8)];
+        for i in 0..reg.size as usize {
+            let bit = reg.offset as usize + i;
+            if (arena.get(bit / 8)? >> (bit % 8)) & 1 != 0 {
+                bytes[i / 8] |= 1 << (i % 8);
+            }
+        }
+        Some(BitVector {
+            width: reg.size,
+            bytes,
+        })
+    }
+
+    /// Registers whose value differs in `other`, sub-registers included.
+    pub fn diff(&self, other: &RegisterFile) -> Vec<RegChange> {
+        self.regs
+            .iter()
+            .filter_map(|r| {
+                let old = self.value_of(r)?;
+                let new = other.value_of(r)?;
+                (old != new).then(|| RegChange {
+                    name: r.name.clone(),
+                    old,
+                    new,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Registers<'_> {
+    fn ptr(&self) -> *mut RzReg {
+        self.reg.as_ptr()
+    }
+
+    fn item(&self, name: &str) -> Result<NonNull<RzRegItem>> {
+        let cname = CString::new(name)?;
+        let item = unsafe { rz_reg_get(self.ptr(), cname.as_ptr(), -1) };
+        NonNull::new(item).ok_or(RizinError::NotFound {
+            kind: "register",
+            name: name.to_owned(),
+        })
+    }
+
+    pub fn all(&self) -> Vec<Register> {
+        // The list belongs to the profile.
+        unsafe { ListRef::<RzRegItem>::from_raw((*self.ptr()).allregs) }
+            .map(|l| l.iter().map(|r| unsafe { Register::from_raw(r) }).collect())
+            .unwrap_or_default()
+    }
+
+    /// The register sets of the profile, including those without registers.
+    pub fn sets(&self) -> Vec<RegSet> {
+        // The sets and their arenas belong to the profile.
+        let regset = unsafe { &(*self.ptr()).regset };
+        regset
+            .iter()
+            .enumerate()
+            .map(|(t, set)| unsafe {
+                RegSet {
+                    reg_type: RegType::from_raw(t as _),
+                    registers: ListRef::<RzRegItem>::from_raw(set.regs)
+                        .map(|l| l.iter().map(|r| Register::from_raw(r)).collect())
+                        .unwrap_or_default(),
+                    arena_size: set.arena.as_ref().map(|a| a.size as _),
+                }
+            })
+            .collect()
+    }
+
+    pub fn of_type(&self, reg_type: RegType) -> Vec<Register> {
+        self.all()
+            .into_iter()
+            .filter(|r| r.reg_type == reg_type)
+            .collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<Register> {
+        let item = self.item(name).ok()?;
+        Some(unsafe { Register::from_raw(item.as_ref()) })
+    }
+
+    /// The register assigned to `role` such as `"PC"`, `"SP"`, `"A0"` or `"ZF"`.
+    pub fn role(&self, role: &str) -> Option<String> {
+        let crole = CString::new(role).ok()?;
+        unsafe {
+            let idx = rz_reg_get_name_idx(crole.as_ptr());
+            if idx < 0 {
+                return None;
+            }
+            cstr_to_string(rz_reg_get_name(self.ptr(), idx as _))
+        }
+    }
+
+    /// All assigned roles as `(role, register)`.
+    pub fn roles(&self) -> Vec<(String, String)> {
+        (0..RzRegisterId_RZ_REG_NAME_LAST)
+            .filter_map(|i| unsafe {
+                let role = cstr_to_string(rz_reg_get_role(i as _))?;
+                let name = cstr_to_string(rz_reg_get_name(self.ptr(), i as _))?;
+                Some((role, name))
+            })
+            .collect()
+    }
+
+    /// The registers that are part of `name`, e.g. `eax`, `ax` and `al` of `rax`.
+    pub fn subregisters(&self, name: &str) -> Vec<Register> {
+        let Some(reg) = self.get(name) else {
+            return vec![];
+        };
+        self.all()
+            .into_iter()
+            .filter(|r| r.is_subregister_of(&reg))
+            .collect()
+    }
+
+    /// The largest register containing `name`.
+    pub fn parent(&self, name: &str) -> Option<Register> {
+        let reg = self.get(name)?;
+        self.all()
+            .into_iter()
+            .filter(|r| reg.is_subregister_of(r))
+            .max_by_key(|r| r.size)
+    }
+
+    pub fn value(&self, name: &str) -> Result<u64> {
+        let item = self.item(name)?;
+        Ok(unsafe { rz_reg_get_value(self.ptr(), item.as_ptr()) })
+    }
+
+    pub fn set_value(&self, name: &str, value: u64) -> Result<()> {
+        let item = self.item(name)?;
+        if unsafe { rz_reg_set_value(self.ptr(), item.as_ptr(), value) } {
+            Ok(())
+        } else {
+            Err(RizinError::Register {
+                name: name.to_owned(),
+            })
+        }
+    }
+
+    pub fn snapshot(&self) -> RegisterFile {
+        let arenas = (0..RzRegisterType_RZ_REG_TYPE_LAST)
+            .map(|t| unsafe {
+                let mut size = 0;
+                let bytes = rz_reg_get_bytes(self.ptr(), t as _, addr_of_mut!(size));
+                if bytes.is_null() {
+                    return vec![];
+                }
+                let res = std::slice::from_raw_parts(bytes, size as _).to_vec();
+                free(bytes as _);
+                res
+            })
+            .collect();
+        RegisterFile {
+            regs: self.all(),
+            arenas,
+        }
+    }
+
+    pub fn restore(&self, file: &RegisterFile) -> Result<()> {
+        for (t, arena) in file.arenas.iter().enumerate() {
+            if arena.is_empty() {
+                continue;
+            }
+            let ok =
+                unsafe { rz_reg_set_bytes(self.ptr(), t as _, arena.as_ptr(), arena.len() as _) };
+            if !ok {
+                return Err(RizinError::Register {
+                    name: format!("arena {}", t),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrapper::*;
+
+    #[test]
+    fn test_registers() {
+        let core = Core::new();
+        core.set("analysis.arch", "x86").unwrap();
+        core.set("analysis.bits", "64").unwrap();
+        let regs = core.registers();
+        assert_eq!(regs.role("PC").as_deref(), Some("rip"));
+        assert!(regs.roles().iter().any(|(r, n)| r == "SP" && n == "rsp"));
+        let rax = regs.get("rax").unwrap();
+        assert_eq!((rax.reg_type, rax.size), (RegType::Gpr, 64));
+        assert!(regs.subregisters("rax").iter().any(|r| r.name == "eax"));
+        assert_eq!(regs.parent("al").unwrap().name, "rax");
+        let sets = regs.sets();
+        let gpr = sets.iter().find(|s| s.reg_type == RegType::Gpr).unwrap();
+        assert!(gpr.registers.iter().any(|r| r.name == "rax"));
+        assert!(gpr.arena_size.is_some_and(|n| n >= 8));
+        assert!(sets
+            .iter()
+            .any(|s| s.reg_type == RegType::Flg && !s.registers.is_empty()));
+    }
+
+    #[test]
+    fn test_register_file() {
+        let core = Core::new();
+        core.set("analysis.arch", "x86").unwrap();
+        core.set("analysis.bits", "64").unwrap();
+        let regs = core.registers();
+        let before = regs.snapshot();
+        regs.set_value("rax", 0x1234).unwrap();
+        let after = regs.snapshot();
+        assert_eq!(after.value("ax").unwrap().to_u64(), 0x1234);
+        let diff = before.diff(&after);
+        assert!(diff
+            .iter()
+            .any(|c| c.name == "rax" && c.new.to_u64() == 0x1234));
+        assert!(diff.iter().all(|c| c.name != "rbx"));
+        regs.restore(&before).unwrap();
+        assert_eq!(regs.value("rax").unwrap(), 0);
+    }
+}
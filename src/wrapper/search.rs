@@ -0,0 +1,558 @@
+use std::collections::VecDeque;
+use std::ffi::{c_void, CString};
+use std::ops::Range;
+use std::ptr::{addr_of_mut, null, NonNull};
+
+use crate::wrapper::{Core, OpMask, OpType, PVector, Result, RizinError};
+use crate::*;
+
+/// Size of the reads fed to the search engine.
+const CHUNK_SIZE: u64 = 0x10000;
+
+/// Bytes past the end of a chunk a regex match can span.
+const REGEX_OVERLAP: u64 = 0x1000;
+
+/// Upper bound of the size of an instruction, for the reads of ROP searches.
+const MAX_OP_SIZE: u64 = 16;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum StrEncoding {
+    #[default]
+    Ascii,
+    /// UTF-16LE, rizin's "wide" strings.
+    Utf16,
+}
+
+/// What a [`Search`] looks for.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Pattern {
+    /// Bytes, optionally with a mask of the bits that must match.
+    Bytes {
+        bytes: Vec<u8>,
+        mask: Option<Vec<u8>>,
+    },
+    Str {
+        text: String,
+        encoding: StrEncoding,
+        icase: bool,
+    },
+    /// A regular expression. Matches crossing a chunk boundary are only
+    /// found if they end within 4KiB of it.
+    Regex(String),
+    /// Instruction sequences ending in a return, at most `max_len` bytes
+    /// long including the return.
+    Rop { max_len: usize },
+}
+
+impl Pattern {
+    /// Parses a hex string such as `"deadbeef"`.
+    pub fn hex(hex: &str) -> Result<Self> {
+        Ok(Self::Bytes {
+            bytes: parse_hex(hex)?,
+            mask: None,
+        })
+    }
+
+    /// Like [`Pattern::hex`], only the bits set in `mask` have to match.
+    pub fn hex_mask(hex: &str, mask: &str) -> Result<Self> {
+        let bytes = parse_hex(hex)?;
+        let mask = parse_hex(mask)?;
+        if bytes.len() != mask.len() {
+            return Err(RizinError::InvalidArgument(
+                "mask length differs from pattern length".to_owned(),
+            ));
+        }
+        Ok(Self::Bytes {
+            bytes,
+            mask: Some(mask),
+        })
+    }
+
+    pub fn string(text: &str, encoding: StrEncoding, icase: bool) -> Self {
+        Self::Str {
+            text: text.to_owned(),
+            encoding,
+            icase,
+        }
+    }
+
+    /// A little endian 32-bit value.
+    pub fn u32_le(value: u32) -> Self {
+        Self::Bytes {
+            bytes: value.to_le_bytes().to_vec(),
+            mask: None,
+        }
+    }
+
+    /// The low `size` bytes of `value` in the given byte order.
+    pub fn value(value: u64, size: usize, big_endian: bool) -> Result<Self> {
+        if !matches!(size, 1 | 2 | 4 | 8) {
+            return Err(RizinError::InvalidArgument(format!(
+                "invalid value size {}",
+                size
+            )));
+        }
+        let bytes = if big_endian {
+            value.to_be_bytes()[8 - size..].to_vec()
+        } else {
+            value.to_le_bytes()[..size].to_vec()
+        };
+        Ok(Self::Bytes { bytes, mask: None })
+    }
+}
+
+fn parse_hex(hex: &str) -> Result<Vec<u8>> {
+    let hex = hex.split_whitespace().collect::<String>();
+    if hex.len() % 2 != 0 {
+        return Err(RizinError::InvalidArgument(format!(
+            "odd length hex {}",
+            hex
+        )));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16)
+                .map_err(|_| RizinError::InvalidArgument(format!("invalid hex {}", hex)))
+        })
+        .collect()
+}
+
+/// A search over the mapped IO of a [`Core`], see [`Core::search`].
+pub struct Search<'a> {
+    core: &'a Core,
+    pattern: Pattern,
+    from: u64,
+    to: u64,
+    max_hits: Option<usize>,
+}
+
+impl Core {
+    pub fn search(&self, pattern: Pattern) -> Search<'_> {
+        Search {
+            core: self,
+            pattern,
+            from: 0,
+            to: u64::MAX,
+            max_hits: None,
+        }
+    }
+}
+
+impl<'a> Search<'a> {
+    /// Limits the search to `[from, to)`.
+    pub fn range(mut self, from: u64, to: u64) -> Self {
+        self.from = from;
+        self.to = to;
+        self
+    }
+
+    pub fn max_hits(mut self, n: usize) -> Self {
+        self.max_hits = Some(n);
+        self
+    }
+
+    /// Mapped ranges inside the search range, sorted by address, with
+    /// overlapping and adjacent maps merged.
+    fn ranges(&self) -> VecDeque<(u64, u64)> {
+        let mut maps = self
+            .core
+            .io()
+            .maps()
+            .into_iter()
+            .filter_map(|m| {
+                let from = m.addr.max(self.from);
+                let to = m.addr.saturating_add(m.size).min(self.to);
+                (from < to).then_some((from, to))
+            })
+            .collect::<Vec<_>>();
+        maps.sort();
+        let mut res = VecDeque::<(u64, u64)>::new();
+        for (from, to) in maps {
+            match res.back_mut() {
+                Some(last) if from <= last.1 => last.1 = last.1.max(to),
+                _ => res.push_back((from, to)),
+            }
+        }
+        res
+    }
+
+    /// Runs the search, yielding `(addr, len)` of each hit.
+    pub fn run(self) -> Result<SearchHits<'a>> {
+        let engine = match &self.pattern {
+            Pattern::Rop { .. } => None,
+            pattern => Some(Engine::new(pattern)?),
+        };
+        Ok(SearchHits {
+            ranges: self.ranges(),
+            engine,
+            pending: VecDeque::new(),
+            found: 0,
+            search: self,
+        })
+    }
+}
+
+/// An `RzSearch` with a single keyword, reporting hits into `hits`.
+struct Engine {
+    search: NonNull<RzSearch>,
+    hits: Vec<(u64, usize)>,
+    /// Bytes read past the end of each chunk, so that matches crossing it are found.
+    overlap: u64,
+    /// The pattern of [`Pattern::Regex`] searches, to measure the matches
+    /// rizin only reports the address of.
+    regex: Option<NonNull<RzRegex>>,
+}
+
+unsafe extern "C" fn collect_hit(kw: *mut RzSearchKeyword, user: *mut c_void, addr: u64) -> i32 {
+    let hits = &mut *(user as *mut Vec<(u64, usize)>);
+    let len = kw.as_ref().map_or(0, |kw| kw.keyword_length as usize);
+    hits.push((addr, len));
+    1
+}
+
+impl Engine {
+    fn new(pattern: &Pattern) -> Result<Self> {
+        let regex =
+            match pattern {
+                Pattern::Regex(re) => {
+                    let cre = CString::new(re.as_str())?;
+                    let regex = unsafe { rz_regex_new(cre.as_ptr(), RZ_REGEX_EXTENDED as _, 0) };
+                    Some(NonNull::new(regex).ok_or_else(|| {
+                        RizinError::InvalidArgument(format!("invalid regex {}", re))
+                    })?)
+                }
+                _ => None,
+            };
+        let mode = if regex.is_some() {
+            RZ_SEARCH_REGEXP
+        } else {
+            RZ_SEARCH_KEYWORD
+        };
+        let Some(search) = NonNull::new(unsafe { rz_search_new(mode as _) }) else {
+            if let Some(regex) = regex {
+                unsafe { rz_regex_free(regex.as_ptr()) };
+            }
+            return Err(RizinError::NullPointer { what: "search" });
+        };
+        let mut engine = Self {
+            search,
+            hits: vec![],
+            overlap: 0,
+            regex,
+        };
+        let kw = unsafe {
+            match pattern {
+                Pattern::Bytes { bytes, mask } => {
+                    let (bm, bmlen) = mask.as_ref().map_or((null(), 0), |m| (m.as_ptr(), m.len()));
+                    rz_search_kw_new(bytes.as_ptr(), bytes.len() as _, bm, bmlen as _, null())
+                }
+                Pattern::Str {
+                    text,
+                    encoding,
+                    icase,
+                } => {
+                    let ctext = CString::new(text.as_str())?;
+                    match encoding {
+                        StrEncoding::Ascii => {
+                            rz_search_kw_new_str(ctext.as_ptr(), null(), null(), *icase)
+                        }
+                        StrEncoding::Utf16 => {
+                            rz_search_kw_new_wide(ctext.as_ptr(), null(), null(), *icase)
+                        }
+                    }
+                }
+                Pattern::Regex(re) => {
+                    let cre = CString::new(re.as_str())?;
+                    rz_search_kw_new_regexp(cre.as_ptr(), null())
+                }
+                Pattern::Rop { .. } => unreachable!(),
+            }
+        };
+        if kw.is_null() {
+            return Err(RizinError::InvalidArgument(
+                "invalid search pattern".to_owned(),
+            ));
+        }
+        engine.overlap = if regex.is_some() {
+            REGEX_OVERLAP
+        } else {
+            unsafe { (*kw).keyword_length.saturating_sub(1) as u64 }
+        };
+        unsafe {
+            // The search takes ownership of the keyword.
+            if !rz_search_kw_add(search.as_ptr(), kw) {
+                rz_search_keyword_free(kw);
+                return Err(RizinError::InvalidArgument(
+                    "invalid search pattern".to_owned(),
+                ));
+            }
+        }
+        Ok(engine)
+    }
+
+    /// Length of the match of the regex starting at `offset` of `buf`.
+    fn regex_len(regex: NonNull<RzRegex>, buf: &[u8], offset: usize) -> Option<usize> {
+        let matches = unsafe {
+            rz_regex_match_first(
+                regex.as_ptr(),
+                buf.as_ptr() as _,
+                buf.len() as _,
+                offset as _,
+                RZ_REGEX_DEFAULT as _,
+            )
+        };
+        // The first match is the whole pattern, the others its groups.
+        PVector::<RzRegexMatch>::try_from(matches)
+            .ok()?
+            .get(0)
+            .filter(|m| m.start as usize == offset)
+            .map(|m| m.len as usize)
+    }
+
+    fn run(&mut self, core: &Core, from: u64, to: u64) -> Vec<(u64, usize)> {
+        unsafe {
+            rz_search_set_callback(
+                self.search.as_ptr(),
+                Some(collect_hit),
+                addr_of_mut!(self.hits) as _,
+            );
+        }
+        let io = core.io();
+        let mut res = vec![];
+        let mut buf = vec![];
+        let mut addr = from;
+        while addr < to {
+            let end = addr.saturating_add(CHUNK_SIZE).min(to);
+            buf.resize(
+                (end.saturating_add(self.overlap).min(to) - addr) as usize,
+                0,
+            );
+            if io.read_at(addr, &mut buf).is_ok() {
+                unsafe {
+                    // Each chunk is searched on its own, hits starting in the
+                    // overlap are left to the next one.
+                    rz_search_begin(self.search.as_ptr());
+                    rz_search_update(self.search.as_ptr(), addr, buf.as_ptr(), buf.len() as _);
+                }
+                let regex = self.regex;
+                res.extend(
+                    self.hits
+                        .drain(..)
+                        .filter(|&(hit, _)| hit < end)
+                        .filter_map(|(hit, len)| match regex {
+                            Some(regex) => Self::regex_len(regex, &buf, (hit - addr) as usize)
+                                .map(|len| (hit, len)),
+                            None => Some((hit, len)),
+                        }),
+                );
+            }
+            addr = end;
+        }
+        res
+    }
+}
+
+impl Drop for Engine {
+    fn drop(&mut self) {
+        unsafe {
+            rz_search_free(self.search.as_ptr());
+            if let Some(regex) = self.regex {
+                rz_regex_free(regex.as_ptr());
+            }
+        }
+    }
+}
+
+/// Finds the gadgets ending at each return instruction in `[from, to)`,
+/// reading a chunk at a time.
+fn rop_gadgets(core: &Core, from: u64, to: u64, max_len: usize) -> Vec<(u64, usize)> {
+    let mut res = vec![];
+    let mut chunk = from;
+    while chunk < to {
+        let end = chunk.saturating_add(CHUNK_SIZE).min(to);
+        // Gadgets ending in this chunk may start before it, and its last
+        // return may end after it.
+        let start = chunk.saturating_sub(max_len as u64).max(from);
+        let mut buf = vec![0u8; (end.saturating_add(MAX_OP_SIZE).min(to) - start) as usize];
+        if core.io().read_at(start, &mut buf).is_ok() {
+            let rets = (chunk - start) as usize..(end - start) as usize;
+            res.extend(
+                rop_gadgets_in(core, &buf, start, rets, max_len)
+                    .into_iter()
+                    .map(|(offset, len)| (start + offset as u64, len)),
+            );
+        }
+        chunk = end;
+    }
+    res
+}
+
+/// Gadgets of `buf` loaded at `addr`, ending in a return starting in `rets`,
+/// as `(offset, len)`. Each offset is decoded at most once.
+fn rop_gadgets_in(
+    core: &Core,
+    buf: &[u8],
+    addr: u64,
+    rets: Range<usize>,
+    max_len: usize,
+) -> Vec<(usize, usize)> {
+    // Decoded op type and size by offset.
+    let mut ops: Vec<Option<Option<(OpType, usize)>>> = vec![None; buf.len()];
+    let mut decode = |offset: usize| {
+        *ops[offset].get_or_insert_with(|| {
+            core.analysis_op_with(&buf[offset..], addr as usize + offset, OpMask::empty())
+                .ok()
+                .filter(|op| op.size() > 0 && !op.is_invalid())
+                .map(|op| (op.op_type(), op.size()))
+        })
+    };
+    let mut res = vec![];
+    for ret in rets {
+        let Some((OpType::Ret, ret_size)) = decode(ret) else {
+            continue;
+        };
+        if ret_size > max_len {
+            continue;
+        }
+        let end = ret + ret_size;
+        // Walking backwards, `reaches[i]` tells whether the ops from
+        // `first + i` fall through to `ret`.
+        let first = end.saturating_sub(max_len);
+        let mut reaches = vec![false; ret - first + 1];
+        reaches[ret - first] = true;
+        for start in (first..ret).rev() {
+            reaches[start - first] = match decode(start) {
+                Some((kind, size))
+                    if !matches!(
+                        kind,
+                        OpType::Jmp
+                            | OpType::UJmp
+                            | OpType::Call
+                            | OpType::UCall
+                            | OpType::Ret
+                            | OpType::Trap
+                            | OpType::Ill
+                    ) =>
+                {
+                    start + size <= ret && reaches[start + size - first]
+                }
+                _ => false,
+            };
+        }
+        res.extend(
+            (first..=ret)
+                .filter(|start| reaches[start - first])
+                .map(|start| (start, end - start)),
+        );
+    }
+    res
+}
+
+/// Iterator over the hits of a [`Search`].
+pub struct SearchHits<'a> {
+    search: Search<'a>,
+    ranges: VecDeque<(u64, u64)>,
+    engine: Option<Engine>,
+    pending: VecDeque<(u64, usize)>,
+    found: usize,
+}
+
+impl Iterator for SearchHits<'_> {
+    type Item = (u64, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.search.max_hits.is_some_and(|n| self.found >= n) {
+            return None;
+        }
+        while self.pending.is_empty() {
+            let (from, to) = self.ranges.pop_front()?;
+            let hits = match (&mut self.engine, &self.search.pattern) {
+                (Some(engine), _) => engine.run(self.search.core, from, to),
+                (None, Pattern::Rop { max_len }) => {
+                    rop_gadgets(self.search.core, from, to, *max_len)
+                }
+                _ => vec![],
+            };
+            self.pending.extend(hits);
+        }
+        self.found += 1;
+        self.pending.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrapper::*;
+
+    fn core_with(data: &[u8]) -> Core {
+        let core = Core::new();
+        core.set("analysis.arch", "x86").unwrap();
+        core.set("analysis.bits", "64").unwrap();
+        let io = core.io();
+        io.open(&format!("malloc://{:#x}", data.len()), Perm::R | Perm::W)
+            .unwrap();
+        io.write_at(0, data).unwrap();
+        drop(io);
+        core
+    }
+
+    #[test]
+    fn test_search_bytes() {
+        let core = core_with(b"xxHello hello\x78\x56\x34\x12xx");
+        let hits = |p| core.search(p).run().unwrap().collect::<Vec<_>>();
+        assert_eq!(hits(Pattern::hex("48656c").unwrap()), [(2, 3)]);
+        assert_eq!(
+            hits(Pattern::string("hello", StrEncoding::Ascii, true)),
+            [(2, 5), (8, 5)]
+        );
+        assert_eq!(hits(Pattern::u32_le(0x12345678)), [(13, 4)]);
+        assert_eq!(hits(Pattern::hex_mask("4800", "ff00").unwrap()), [(2, 2)]);
+        let first = core
+            .search(Pattern::string("hello", StrEncoding::Ascii, true))
+            .range(4, 0x100)
+            .max_hits(1)
+            .run()
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(first, [(8, 5)]);
+    }
+
+    #[test]
+    fn test_search_regex() {
+        let core = core_with(b"xxHello hallo");
+        let hits = core
+            .search(Pattern::Regex("h[ae]llo".to_owned()))
+            .run()
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(hits, [(8, 5)]);
+    }
+
+    #[test]
+    fn test_search_chunk_boundary() {
+        let mut data = vec![0u8; 0x10004];
+        data[0xfffe..0x10002].copy_from_slice(b"abcd");
+        let core = core_with(&data);
+        let hits = core
+            .search(Pattern::string("abcd", StrEncoding::Ascii, false))
+            .run()
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(hits, [(0xfffe, 4)]);
+    }
+
+    #[test]
+    fn test_search_rop() {
+        // nop; pop rdi; ret
+        let core = core_with(&[0x90, 0x5f, 0xc3]);
+        let hits = |max_len| {
+            core.search(Pattern::Rop { max_len })
+                .run()
+                .unwrap()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(hits(2), [(1, 2), (2, 1)]);
+        assert_eq!(hits(3), [(0, 3), (1, 2), (2, 1)]);
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::path::Path;
+use std::ptr::{addr_of_mut, null_mut, NonNull};
+
+use crate::wrapper::{cstr_to_string, Core, ListRef, Result, RizinError};
+use crate::*;
+
+/// Prefix rizin gives functions renamed by a signature.
+const FLIRT_PREFIX: &str = "flirt.";
+
+/// A function identified by [`Core::apply_signatures`].
+///
+/// FLIRT matches are exact, so there is no confidence score.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SigMatch {
+    pub addr: u64,
+    /// Name given by the signature, without rizin's `flirt.` prefix.
+    pub name: String,
+    /// Length of the matched module, which may define other functions too.
+    pub matched_bytes: u64,
+}
+
+fn path_cstr(path: &Path) -> Result<CString> {
+    Ok(CString::new(path.to_str().ok_or(RizinError::Utf8)?)?)
+}
+
+/// Parses the signatures of `path` like `rz_sign_flirt_apply` does.
+unsafe fn parse_signatures(path: &Path) -> Result<NonNull<RzFlirtNode>> {
+    let buf = rz_buf_new_slurp(path_cstr(path)?.as_ptr());
+    if buf.is_null() {
+        return Err(RizinError::OpenFailed {
+            path: path.display().to_string(),
+        });
+    }
+    let node = if path.extension().is_some_and(|e| e == "pat") {
+        rz_sign_flirt_parse_string_pattern_from_buffer(
+            buf,
+            RZ_FLIRT_NODE_OPTIMIZE_NORMAL as _,
+            null_mut(),
+        )
+    } else {
+        rz_sign_flirt_parse_compressed_pattern_from_buffer(
+            buf,
+            RZ_FLIRT_SIG_ARCH_ANY as _,
+            null_mut(),
+        )
+    };
+    rz_buf_free(buf);
+    NonNull::new(node).ok_or(RizinError::Parse {
+        what: path.display().to_string(),
+    })
+}
+
+/// Collects the length of the module defining each public function under `node`.
+unsafe fn module_lengths(node: &RzFlirtNode, res: &mut HashMap<String, u64>) {
+    for module in ListRef::<RzFlirtModule>::from_raw(node.module_list)
+        .into_iter()
+        .flatten()
+    {
+        for f in ListRef::<RzFlirtFunction>::from_raw(module.public_functions)
+            .into_iter()
+            .flatten()
+        {
+            if let Some(name) = cstr_to_string(f.name.as_ptr()) {
+                res.entry(name).or_insert(module.length as _);
+            }
+        }
+    }
+    for child in ListRef::<RzFlirtNode>::from_raw(node.child_list)
+        .into_iter()
+        .flatten()
+    {
+        module_lengths(child, res);
+    }
+}
+
+impl Core {
+    fn rename_function(&mut self, addr: u64, name: &str) -> bool {
+        let Ok(cname) = CString::new(name) else {
+            return false;
+        };
+        unsafe {
+            let fcn = rz_analysis_get_function_at(self.0.as_ref().analysis, addr);
+            !fcn.is_null() && rz_analysis_function_rename(fcn, cname.as_ptr())
+        }
+    }
+
+    /// Applies a FLIRT `.sig` or `.pat` file to the analyzed functions and
+    /// reports the ones it renamed.
+    pub fn apply_signatures<P: AsRef<Path>>(&mut self, path: P) -> Result<Vec<SigMatch>> {
+        let path = path.as_ref();
+        let cpath = path_cstr(path)?;
+        let mut lengths = HashMap::new();
+        unsafe {
+            let node = parse_signatures(path)?;
+            module_lengths(node.as_ref(), &mut lengths);
+            rz_sign_flirt_node_free(node.as_ptr());
+        }
+        // Functions named by an earlier run get a default name, so that a
+        // `flirt.` name afterwards always means a match of this run.
+        let renamed = self
+            .functions()
+            .iter()
+            .map(|f| (f.addr(), f.name()))
+            .filter(|(_, name)| name.starts_with(FLIRT_PREFIX))
+            .collect::<HashMap<_, _>>();
+        for &addr in renamed.keys() {
+            self.rename_function(addr, &format!("fcn.{:08x}", addr));
+        }
+        let ok = unsafe {
+            rz_sign_flirt_apply(
+                self.0.as_ref().analysis,
+                cpath.as_ptr(),
+                RZ_FLIRT_SIG_ARCH_ANY as _,
+            )
+        };
+        let mut res = self
+            .functions()
+            .iter()
+            .filter_map(|f| {
+                let name = f.name();
+                let sig = name.strip_prefix(FLIRT_PREFIX)?;
+                Some(SigMatch {
+                    addr: f.addr(),
+                    name: sig.to_owned(),
+                    matched_bytes: lengths.get(sig).copied().unwrap_or_default(),
+                })
+            })
+            .collect::<Vec<_>>();
+        for (addr, name) in renamed {
+            if !res.iter().any(|m| m.addr == addr) {
+                self.rename_function(addr, &name);
+            }
+        }
+        if !ok {
+            return Err(RizinError::Signature {
+                path: path.display().to_string(),
+            });
+        }
+        res.sort_by_key(|m| m.addr);
+        Ok(res)
+    }
+
+    /// Writes signatures of the named functions to `path`, as `.sig` or
+    /// `.pat` depending on its extension. Returns the number of signatures.
+    pub fn write_signatures<P: AsRef<Path>>(&self, path: P) -> Result<u32> {
+        let path = path.as_ref();
+        let cpath = path_cstr(path)?;
+        let mut written = 0;
+        let ok = unsafe {
+            rz_core_flirt_create_file(self.0.as_ptr(), cpath.as_ptr(), addr_of_mut!(written))
+        };
+        if ok {
+            Ok(written)
+        } else {
+            Err(RizinError::Signature {
+                path: path.display().to_string(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use crate::wrapper::*;
+
+    fn core_with_function() -> Core {
+        let mut core = Core::new();
+        core.set("analysis.arch", "x86").unwrap();
+        core.set("analysis.bits", "64").unwrap();
+        // push rbp; mov rbp, rsp; nop * 56; pop rbp; ret
+        let mut code = vec![0x55, 0x48, 0x89, 0xe5];
+        code.extend([0x90; 56]);
+        code.extend([0x5d, 0xc3]);
+        let io = core.io();
+        io.open("malloc://0x100", Perm::R | Perm::W | Perm::X)
+            .unwrap();
+        io.write_at(0, &code).unwrap();
+        drop(io);
+        core.analyze_function(0).unwrap();
+        core
+    }
+
+    #[test]
+    fn test_signatures() {
+        let path = env::temp_dir().join(format!("rizin-rs-{}.pat", std::process::id()));
+        let mut core = core_with_function();
+        core.cmd("afn my_func @ 0").unwrap();
+        assert_eq!(core.write_signatures(&path).unwrap(), 1);
+
+        let mut core = core_with_function();
+        let matches = core.apply_signatures(&path).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!((matches[0].addr, matches[0].name.as_str()), (0, "my_func"));
+        assert_eq!(matches[0].matched_bytes, 62);
+
+        // Applying again still reports the function it already named.
+        let matches = core.apply_signatures(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(core.function_at(0).unwrap().name(), "flirt.my_func");
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::HashSet;
+
+use crate::wrapper::{cstr_to_string, BinFile, PVector};
+use crate::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StringEncoding {
+    Ascii,
+    Utf8,
+    Mutf8,
+    Utf16Le,
+    Utf16Be,
+    Utf32Le,
+    Utf32Be,
+    Ibm037,
+    Ibm290,
+    EbcdicUk,
+    EbcdicUs,
+    EbcdicEs,
+    Other(u32),
+}
+
+impl StringEncoding {
+    fn from_raw(enc: RzStrEnc) -> Self {
+        match enc {
+            RzStrEnc_RZ_STRING_ENC_8BIT => Self::Ascii,
+            RzStrEnc_RZ_STRING_ENC_UTF8 => Self::Utf8,
+            RzStrEnc_RZ_STRING_ENC_MUTF8 => Self::Mutf8,
+            RzStrEnc_RZ_STRING_ENC_UTF16LE => Self::Utf16Le,
+            RzStrEnc_RZ_STRING_ENC_UTF16BE => Self::Utf16Be,
+            RzStrEnc_RZ_STRING_ENC_UTF32LE => Self::Utf32Le,
+            RzStrEnc_RZ_STRING_ENC_UTF32BE => Self::Utf32Be,
+            RzStrEnc_RZ_STRING_ENC_IBM037 => Self::Ibm037,
+            RzStrEnc_RZ_STRING_ENC_IBM290 => Self::Ibm290,
+            RzStrEnc_RZ_STRING_ENC_EBCDIC_UK => Self::EbcdicUk,
+            RzStrEnc_RZ_STRING_ENC_EBCDIC_US => Self::EbcdicUs,
+            RzStrEnc_RZ_STRING_ENC_EBCDIC_ES => Self::EbcdicEs,
+            x => Self::Other(x as _),
+        }
+    }
+
+    fn to_raw(self) -> RzStrEnc {
+        match self {
+            Self::Ascii => RzStrEnc_RZ_STRING_ENC_8BIT,
+            Self::Utf8 => RzStrEnc_RZ_STRING_ENC_UTF8,
+            Self::Mutf8 => RzStrEnc_RZ_STRING_ENC_MUTF8,
+            Self::Utf16Le => RzStrEnc_RZ_STRING_ENC_UTF16LE,
+            Self::Utf16Be => RzStrEnc_RZ_STRING_ENC_UTF16BE,
+            Self::Utf32Le => RzStrEnc_RZ_STRING_ENC_UTF32LE,
+            Self::Utf32Be => RzStrEnc_RZ_STRING_ENC_UTF32BE,
+            Self::Ibm037 => RzStrEnc_RZ_STRING_ENC_IBM037,
+            Self::Ibm290 => RzStrEnc_RZ_STRING_ENC_IBM290,
+            Self::EbcdicUk => RzStrEnc_RZ_STRING_ENC_EBCDIC_UK,
+            Self::EbcdicUs => RzStrEnc_RZ_STRING_ENC_EBCDIC_US,
+            Self::EbcdicEs => RzStrEnc_RZ_STRING_ENC_EBCDIC_ES,
+            Self::Other(x) => x as _,
+        }
+    }
+}
+
+/// Options of [`BinFile::strings`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StringsOpts {
+    pub min_length: usize,
+    /// Encodings to look for, rizin guesses the encoding of each string if empty.
+    pub encodings: Vec<StringEncoding>,
+    /// Scan the whole file like `izz` instead of the data sections like `iz`.
+    pub whole_file: bool,
+    pub max_count: Option<usize>,
+}
+
+impl Default for StringsOpts {
+    fn default() -> Self {
+        Self {
+            min_length: 4,
+            encodings: vec![],
+            whole_file: false,
+            max_count: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BinString {
+    pub vaddr: u64,
+    pub paddr: u64,
+    /// Length in characters.
+    pub length: u32,
+    /// Size in bytes.
+    pub size: u32,
+    pub encoding: StringEncoding,
+    pub text: String,
+}
+
+impl BinString {
+    unsafe fn from_raw(s: &RzBinString) -> Self {
+        Self {
+            vaddr: s.vaddr,
+            paddr: s.paddr,
+            length: s.length as _,
+            size: s.size as _,
+            encoding: StringEncoding::from_raw(s.type_),
+            text: cstr_to_string(s.string).unwrap_or_default(),
+        }
+    }
+}
+
+impl BinFile<'_> {
+    unsafe fn strings_with(&self, opts: &StringsOpts, enc: RzStrEnc) -> Vec<BinString> {
+        let cfg = &mut (*self.bin.as_ptr()).str_search_cfg;
+        let old = (cfg.min_length, cfg.string_encoding);
+        cfg.min_length = opts.min_length as _;
+        cfg.string_encoding = enc;
+        let strings = rz_bin_file_strings(self.bf.as_ptr(), opts.min_length as _, opts.whole_file);
+        let cfg = &mut (*self.bin.as_ptr()).str_search_cfg;
+        (cfg.min_length, cfg.string_encoding) = old;
+        PVector::<RzBinString>::try_from(strings)
+            .map(|v| v.iter().map(|s| BinString::from_raw(s)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Extracts strings like `iz`, or `izz` with [`StringsOpts::whole_file`].
+    pub fn strings(&self, opts: &StringsOpts) -> Vec<BinString> {
+        let mut res = if opts.encodings.is_empty() {
+            unsafe { self.strings_with(opts, RzStrEnc_RZ_STRING_ENC_GUESS) }
+        } else {
+            let mut seen = HashSet::new();
+            let mut res = vec![];
+            for enc in &opts.encodings {
+                for s in unsafe { self.strings_with(opts, enc.to_raw()) } {
+                    if seen.insert((s.paddr, s.encoding)) {
+                        res.push(s);
+                    }
+                }
+            }
+            res
+        };
+        res.sort_by_key(|s| s.paddr);
+        if let Some(n) = opts.max_count {
+            res.truncate(n);
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::wrapper::*;
+
+    #[test]
+    fn test_strings() {
+        let path = Path::new("/bin/ls");
+        if !path.exists() {
+            return;
+        }
+        let mut bin = Bin::new();
+        let bf = bin.open(path).unwrap();
+        let strings = bf.strings(&StringsOpts::default());
+        assert!(!strings.is_empty());
+        assert!(strings.iter().all(|s| s.length >= 4));
+        assert!(strings.windows(2).all(|w| w[0].paddr <= w[1].paddr));
+        let opts = StringsOpts {
+            min_length: 8,
+            encodings: vec![StringEncoding::Ascii],
+            whole_file: true,
+            max_count: Some(10),
+        };
+        let strings = bf.strings(&opts);
+        assert!(strings.len() <= 10);
+        assert!(strings
+            .iter()
+            .all(|s| s.encoding == StringEncoding::Ascii && s.length >= 8));
+    }
+}
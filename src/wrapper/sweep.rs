@@ -0,0 +1,183 @@
+use crate::wrapper::{AnalysisOp, Core, OpMask, Result, RizinError};
+
+/// What a [`Disassembler`] does after bytes that don't decode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Resync {
+    /// Retry at the next byte.
+    #[default]
+    SkipByte,
+    /// Retry at the next address aligned to the given instruction alignment.
+    SkipAlign(usize),
+    /// End the sweep.
+    Stop,
+}
+
+/// Linear sweep over a buffer, yielding one op per instruction.
+///
+/// Undecodable bytes are reported as an `Err` carrying their address, then
+/// the sweep continues according to its [`Resync`] policy.
+pub struct Disassembler<'a> {
+    core: &'a Core,
+    bytes: &'a [u8],
+    base: u64,
+    offset: usize,
+    mask: OpMask,
+    resync: Resync,
+    done: bool,
+}
+
+impl<'a> Disassembler<'a> {
+    pub fn mask(mut self, mask: OpMask) -> Self {
+        self.mask = mask;
+        self
+    }
+
+    pub fn resync(mut self, resync: Resync) -> Self {
+        self.resync = resync;
+        self
+    }
+
+    /// Address of the next instruction to decode.
+    pub fn addr(&self) -> u64 {
+        self.base.wrapping_add(self.offset as u64)
+    }
+}
+
+impl Iterator for Disassembler<'_> {
+    type Item = Result<AnalysisOp>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.offset >= self.bytes.len() {
+            return None;
+        }
+        let addr = self.addr();
+        let res = self
+            .core
+            .analysis_op_with(&self.bytes[self.offset..], addr as _, self.mask)
+            .and_then(|op| {
+                if op.size() == 0 || op.is_invalid() {
+                    Err(RizinError::InvalidInstruction { addr })
+                } else {
+                    Ok(op)
+                }
+            });
+        match &res {
+            Ok(op) => self.offset += op.size(),
+            Err(_) => match self.resync {
+                Resync::SkipByte => self.offset += 1,
+                Resync::SkipAlign(align) => {
+                    let align = align.max(1) as u64;
+                    self.offset += (align - addr % align) as usize;
+                }
+                Resync::Stop => self.done = true,
+            },
+        }
+        Some(res)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            (0, Some(0))
+        } else {
+            (0, Some(self.bytes.len().saturating_sub(self.offset)))
+        }
+    }
+}
+
+impl Core {
+    /// Linear sweep over `bytes` loaded at `base_addr`, see [`Disassembler`].
+    pub fn disassemble<'a>(&'a self, bytes: &'a [u8], base_addr: u64) -> Disassembler<'a> {
+        Disassembler {
+            core: self,
+            bytes,
+            base: base_addr,
+            offset: 0,
+            mask: OpMask::DISASM | OpMask::IL,
+            resync: Resync::default(),
+            done: false,
+        }
+    }
+
+    /// Sweeps each `(bytes, base_addr)` buffer in turn with the same policy.
+    pub fn disassemble_many<'a, I>(
+        &'a self,
+        buffers: I,
+        mask: OpMask,
+        resync: Resync,
+    ) -> impl Iterator<Item = Result<AnalysisOp>> + 'a
+    where
+        I: IntoIterator<Item = (&'a [u8], u64)>,
+        I::IntoIter: 'a,
+    {
+        buffers
+            .into_iter()
+            .flat_map(move |(bytes, base)| self.disassemble(bytes, base).mask(mask).resync(resync))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrapper::*;
+
+    #[test]
+    fn test_disassemble() {
+        let core = Core::new();
+        core.set("analysis.arch", "x86").unwrap();
+        core.set("analysis.bits", "64").unwrap();
+        // nop; push rbp; ret
+        let bytes = [0x90, 0x55, 0xc3];
+        let addrs = core
+            .disassemble(&bytes, 0x1000)
+            .map(|op| op.unwrap().addr())
+            .collect::<Vec<_>>();
+        assert_eq!(addrs, [0x1000, 0x1001, 0x1002]);
+    }
+
+    #[test]
+    fn test_disassemble_resync() {
+        let core = Core::new();
+        core.set("analysis.arch", "x86").unwrap();
+        core.set("analysis.bits", "64").unwrap();
+        // nop; (bad); nop
+        let bytes = [0x90, 0x06, 0x90];
+        let ops = core.disassemble(&bytes, 0).collect::<Vec<_>>();
+        assert_eq!(ops.len(), 3);
+        assert!(matches!(
+            ops[1],
+            Err(RizinError::InvalidInstruction { addr: 1 })
+        ));
+        let ops = core
+            .disassemble(&bytes, 0)
+            .resync(Resync::Stop)
+            .collect::<Vec<_>>();
+        assert_eq!(ops.len(), 2);
+    }
+
+    #[test]
+    fn test_disassemble_skip_align_past_end() {
+        let core = Core::new();
+        core.set("analysis.arch", "x86").unwrap();
+        core.set("analysis.bits", "64").unwrap();
+        // (bad); nop; nop
+        let bytes = [0x06, 0x90, 0x90];
+        let mut ops = core.disassemble(&bytes, 0).resync(Resync::SkipAlign(4));
+        assert!(ops.next().unwrap().is_err());
+        assert_eq!(ops.addr(), 4);
+        assert_eq!(ops.size_hint(), (0, Some(0)));
+        assert!(ops.next().is_none());
+    }
+
+    #[test]
+    fn test_disassemble_wraps() {
+        let core = Core::new();
+        core.set("analysis.arch", "x86").unwrap();
+        core.set("analysis.bits", "64").unwrap();
+        let bytes = [0x90; 3];
+        let mut ops = core.disassemble(&bytes, u64::MAX - 1);
+        let mut addrs = vec![];
+        while ops.next().is_some() {
+            addrs.push(ops.addr());
+        }
+        assert_eq!(addrs, [u64::MAX, 0, 1]);
+    }
+}
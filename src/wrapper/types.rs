@@ -0,0 +1,579 @@
+use std::ffi::{c_char, CString};
+use std::fmt::Write;
+use std::marker::PhantomData;
+use std::path::Path;
+use std::ptr::{addr_of_mut, null, null_mut, NonNull};
+
+use crate::wrapper::{
+    cstr_to_string, vector_slice, Core, Function, List, PVectorRef, Result, RizinError,
+};
+use crate::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BaseTypeKind {
+    Struct,
+    Union,
+    Enum,
+    Typedef,
+    Atomic,
+}
+
+impl BaseTypeKind {
+    fn from_raw(kind: RzBaseTypeKind) -> Option<Self> {
+        match kind {
+            RzBaseTypeKind_RZ_BASE_TYPE_KIND_STRUCT => Some(Self::Struct),
+            RzBaseTypeKind_RZ_BASE_TYPE_KIND_UNION => Some(Self::Union),
+            RzBaseTypeKind_RZ_BASE_TYPE_KIND_ENUM => Some(Self::Enum),
+            RzBaseTypeKind_RZ_BASE_TYPE_KIND_TYPEDEF => Some(Self::Typedef),
+            RzBaseTypeKind_RZ_BASE_TYPE_KIND_ATOMIC => Some(Self::Atomic),
+            _ => None,
+        }
+    }
+
+    fn to_raw(self) -> RzBaseTypeKind {
+        match self {
+            Self::Struct => RzBaseTypeKind_RZ_BASE_TYPE_KIND_STRUCT,
+            Self::Union => RzBaseTypeKind_RZ_BASE_TYPE_KIND_UNION,
+            Self::Enum => RzBaseTypeKind_RZ_BASE_TYPE_KIND_ENUM,
+            Self::Typedef => RzBaseTypeKind_RZ_BASE_TYPE_KIND_TYPEDEF,
+            Self::Atomic => RzBaseTypeKind_RZ_BASE_TYPE_KIND_ATOMIC,
+        }
+    }
+}
+
+/// A struct or union member.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Member {
+    pub name: String,
+    pub type_name: String,
+    /// Offset in bytes, always 0 for union members.
+    pub offset: u64,
+    /// Size in bytes.
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EnumCase {
+    pub name: String,
+    pub value: i64,
+}
+
+/// A snapshot of an `RzBaseType`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BaseType {
+    pub name: String,
+    pub kind: BaseTypeKind,
+    /// Size in bytes.
+    pub size: u64,
+    /// Members of structs and unions.
+    pub members: Vec<Member>,
+    /// Cases of enums.
+    pub cases: Vec<EnumCase>,
+    /// The aliased type of typedefs.
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CallableArg {
+    pub name: String,
+    pub type_name: String,
+}
+
+/// A snapshot of an `RzCallable`, i.e. a function prototype.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Callable {
+    pub name: String,
+    pub ret: Option<String>,
+    pub args: Vec<CallableArg>,
+    pub cc: Option<String>,
+    pub noreturn: bool,
+}
+
+/// The type database of a [`Core`]'s analysis.
+pub struct TypeDb<'a> {
+    pub db: NonNull<RzTypeDB>,
+    marker: PhantomData<&'a Core>,
+}
+
+impl Core {
+    pub fn types(&self) -> TypeDb<'_> {
+        let db = unsafe { (*self.0.as_ref().analysis).typedb };
+        TypeDb {
+            db: NonNull::new(db).unwrap(),
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Takes ownership of a string allocated by rizin.
+unsafe fn take_string(s: *mut c_char) -> Option<String> {
+    let res = cstr_to_string(s);
+    free(s as _);
+    res
+}
+
+fn parse_result(res: i32, err: *mut c_char) -> Result<()> {
+    let msg = unsafe { take_string(err) };
+    if res == 0 {
+        Ok(())
+    } else {
+        Err(RizinError::Parse {
+            what: format!("type: {}", msg.unwrap_or_default().trim()),
+        })
+    }
+}
+
+impl TypeDb<'_> {
+    fn ptr(&self) -> *mut RzTypeDB {
+        self.db.as_ptr()
+    }
+
+    /// Adds the types and prototypes declared in C `code`.
+    pub fn parse(&self, code: &str) -> Result<()> {
+        let ccode = CString::new(code)?;
+        let mut err = null_mut();
+        let res = unsafe {
+            rz_type_parse_string_stateless((*self.ptr()).parser, ccode.as_ptr(), addr_of_mut!(err))
+        };
+        parse_result(res, err)
+    }
+
+    /// Adds the types and prototypes declared in a C header.
+    pub fn parse_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let cpath = CString::new(path.to_str().ok_or(RizinError::Utf8)?)?;
+        let mut err = null_mut();
+        let res =
+            unsafe { rz_type_parse_file(self.ptr(), cpath.as_ptr(), null(), addr_of_mut!(err)) };
+        parse_result(res, err)
+    }
+
+    fn type_string(&self, ty: *const RzType) -> String {
+        if ty.is_null() {
+            return String::new();
+        }
+        unsafe { take_string(rz_type_as_string(self.ptr(), ty)) }.unwrap_or_default()
+    }
+
+    fn bytes(&self, ty: *const RzType) -> u64 {
+        if ty.is_null() {
+            0
+        } else {
+            unsafe { rz_type_db_get_bitsize(self.ptr(), ty as _) / 8 }
+        }
+    }
+
+    fn raw_base_type(&self, name: &str) -> Result<NonNull<RzBaseType>> {
+        let cname = CString::new(name)?;
+        let bt = unsafe { rz_type_db_get_base_type(self.ptr(), cname.as_ptr()) };
+        NonNull::new(bt).ok_or(RizinError::NotFound {
+            kind: "type",
+            name: name.to_owned(),
+        })
+    }
+
+    unsafe fn snapshot(&self, bt: &RzBaseType) -> Option<BaseType> {
+        let kind = BaseTypeKind::from_raw(bt.kind)?;
+        let mut res = BaseType {
+            name: cstr_to_string(bt.name).unwrap_or_default(),
+            kind,
+            size: rz_type_db_base_get_bitsize(self.ptr(), bt as *const _ as _) / 8,
+            members: vec![],
+            cases: vec![],
+            target: None,
+        };
+        let data = &bt.__bindgen_anon_1;
+        match kind {
+            BaseTypeKind::Struct => {
+                let members: &[RzTypeStructMember] = vector_slice(&data.struct_data.members);
+                res.members = members
+                    .iter()
+                    .map(|m| Member {
+                        name: cstr_to_string(m.name).unwrap_or_default(),
+                        type_name: self.type_string(m.type_),
+                        offset: m.offset as _,
+                        size: self.bytes(m.type_),
+                    })
+                    .collect();
+            }
+            BaseTypeKind::Union => {
+                let members: &[RzTypeUnionMember] = vector_slice(&data.union_data.members);
+                res.members = members
+                    .iter()
+                    .map(|m| Member {
+                        name: cstr_to_string(m.name).unwrap_or_default(),
+                        type_name: self.type_string(m.type_),
+                        offset: 0,
+                        size: self.bytes(m.type_),
+                    })
+                    .collect();
+            }
+            BaseTypeKind::Enum => {
+                let cases: &[RzTypeEnumCase] = vector_slice(&data.enum_data.cases);
+                res.cases = cases
+                    .iter()
+                    .map(|c| EnumCase {
+                        name: cstr_to_string(c.name).unwrap_or_default(),
+                        value: c.val as _,
+                    })
+                    .collect();
+            }
+            BaseTypeKind::Typedef | BaseTypeKind::Atomic => {
+                if !bt.type_.is_null() {
+                    res.target = Some(self.type_string(bt.type_));
+                }
+            }
+        }
+        Some(res)
+    }
+
+    pub fn base_type(&self, name: &str) -> Option<BaseType> {
+        let bt = self.raw_base_type(name).ok()?;
+        unsafe { self.snapshot(bt.as_ref()) }
+    }
+
+    pub fn base_types(&self, kind: BaseTypeKind) -> Vec<BaseType> {
+        let list = unsafe { rz_type_db_get_base_types_of_kind(self.ptr(), kind.to_raw()) };
+        // Only the list is owned, the types belong to the db.
+        List::<RzBaseType>::try_from(list)
+            .map(|l| {
+                l.iter()
+                    .filter_map(|bt| unsafe { self.snapshot(bt) })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn structs(&self) -> Vec<BaseType> {
+        self.base_types(BaseTypeKind::Struct)
+    }
+
+    pub fn unions(&self) -> Vec<BaseType> {
+        self.base_types(BaseTypeKind::Union)
+    }
+
+    pub fn enums(&self) -> Vec<BaseType> {
+        self.base_types(BaseTypeKind::Enum)
+    }
+
+    unsafe fn callable_snapshot(&self, c: &RzCallable) -> Callable {
+        let args = PVectorRef::<RzCallableArg>::from_raw(c.args)
+            .map(|v| {
+                v.iter()
+                    .map(|a| CallableArg {
+                        name: cstr_to_string(a.name).unwrap_or_default(),
+                        type_name: self.type_string(a.type_),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Callable {
+            name: cstr_to_string(c.name).unwrap_or_default(),
+            ret: (!c.ret.is_null()).then(|| self.type_string(c.ret)),
+            args,
+            cc: cstr_to_string(c.cc),
+            noreturn: c.noret,
+        }
+    }
+
+    /// The prototype of the function `name`.
+    pub fn callable(&self, name: &str) -> Option<Callable> {
+        let cname = CString::new(name).ok()?;
+        let c = unsafe { rz_type_func_get(self.ptr(), cname.as_ptr()) };
+        unsafe { c.as_ref().map(|c| self.callable_snapshot(c)) }
+    }
+
+    /// Renders the type `name` as Rust definitions for use in FFI code.
+    ///
+    /// Structs and unions become `#[repr(C)]` items, enums a type alias with
+    /// one constant per case, typedefs a type alias.
+    pub fn to_rust(&self, name: &str) -> Result<String> {
+        let bt = self.raw_base_type(name)?;
+        let bt = unsafe { bt.as_ref() };
+        let ident = rust_ident(name);
+        let data = &bt.__bindgen_anon_1;
+        let mut res = String::new();
+        unsafe {
+            match BaseTypeKind::from_raw(bt.kind) {
+                Some(kind @ (BaseTypeKind::Struct | BaseTypeKind::Union)) => {
+                    let members: Vec<(*const c_char, *mut RzType)> = if kind == BaseTypeKind::Struct
+                    {
+                        vector_slice::<RzTypeStructMember>(&data.struct_data.members)
+                            .iter()
+                            .map(|m| (m.name as _, m.type_))
+                            .collect()
+                    } else {
+                        vector_slice::<RzTypeUnionMember>(&data.union_data.members)
+                            .iter()
+                            .map(|m| (m.name as _, m.type_))
+                            .collect()
+                    };
+                    let item = if kind == BaseTypeKind::Struct {
+                        "struct"
+                    } else {
+                        "union"
+                    };
+                    writeln!(res, "#[repr(C)]")?;
+                    writeln!(res, "pub {} {} {{", item, ident)?;
+                    for (name, ty) in members {
+                        let name = rust_ident(&cstr_to_string(name).unwrap_or_default());
+                        writeln!(res, "    pub {}: {},", name, self.rust_type(ty))?;
+                    }
+                    writeln!(res, "}}")?;
+                }
+                Some(BaseTypeKind::Enum) => {
+                    writeln!(res, "pub type {} = i32;", ident)?;
+                    for c in vector_slice::<RzTypeEnumCase>(&data.enum_data.cases) {
+                        let case = rust_ident(&cstr_to_string(c.name).unwrap_or_default());
+                        writeln!(res, "pub const {}_{}: {} = {};", ident, case, ident, c.val)?;
+                    }
+                }
+                Some(BaseTypeKind::Typedef | BaseTypeKind::Atomic) => {
+                    if bt.type_.is_null() {
+                        let size = rz_type_db_base_get_bitsize(self.ptr(), bt as *const _ as _) / 8;
+                        writeln!(res, "pub type {} = [u8; {}];", ident, size)?;
+                    } else {
+                        writeln!(res, "pub type {} = {};", ident, self.rust_type(bt.type_))?;
+                    }
+                }
+                None => {
+                    return Err(RizinError::Unsupported(format!(
+                        "unknown kind of type {}",
+                        name
+                    )))
+                }
+            }
+        }
+        Ok(res)
+    }
+
+    unsafe fn rust_type(&self, ty: *const RzType) -> String {
+        let Some(ty) = ty.as_ref() else {
+            return "core::ffi::c_void".to_owned();
+        };
+        let data = &ty.__bindgen_anon_1;
+        match ty.kind {
+            RzTypeKind_RZ_TYPE_KIND_IDENTIFIER => {
+                let name = cstr_to_string(data.identifier.name).unwrap_or_default();
+                rust_primitive(&name)
+                    .map(str::to_owned)
+                    .unwrap_or_else(|| rust_ident(&name))
+            }
+            RzTypeKind_RZ_TYPE_KIND_POINTER => {
+                let pointee = data.pointer.type_;
+                // Callables already render as a function pointer.
+                if pointee
+                    .as_ref()
+                    .is_some_and(|p| p.kind == RzTypeKind_RZ_TYPE_KIND_CALLABLE)
+                {
+                    return self.rust_type(pointee);
+                }
+                let is_const = pointee.as_ref().is_some_and(|p| {
+                    p.kind == RzTypeKind_RZ_TYPE_KIND_IDENTIFIER
+                        && p.__bindgen_anon_1.identifier.is_const
+                });
+                let ptr = if is_const || data.pointer.is_const {
+                    "*const"
+                } else {
+                    "*mut"
+                };
+                format!("{} {}", ptr, self.rust_type(pointee))
+            }
+            RzTypeKind_RZ_TYPE_KIND_ARRAY => {
+                format!(
+                    "[{}; {}]",
+                    self.rust_type(data.array.type_),
+                    data.array.count
+                )
+            }
+            RzTypeKind_RZ_TYPE_KIND_CALLABLE if !data.callable.is_null() => {
+                self.rust_fn(&*data.callable)
+            }
+            _ => "Option<unsafe extern \"C\" fn()>".to_owned(),
+        }
+    }
+
+    /// Renders `c` as a nullable function pointer.
+    unsafe fn rust_fn(&self, c: &RzCallable) -> String {
+        let args = PVectorRef::<RzCallableArg>::from_raw(c.args)
+            .map(|v| {
+                v.iter()
+                    .map(|a| self.rust_type(a.type_))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let is_void = c.ret.as_ref().map_or(true, |r| {
+            r.kind == RzTypeKind_RZ_TYPE_KIND_IDENTIFIER
+                && cstr_to_string(r.__bindgen_anon_1.identifier.name).as_deref() == Some("void")
+        });
+        let ret = if is_void {
+            String::new()
+        } else {
+            format!(" -> {}", self.rust_type(c.ret))
+        };
+        format!("Option<unsafe extern \"C\" fn({}){}>", args.join(", "), ret)
+    }
+}
+
+fn rust_primitive(name: &str) -> Option<&'static str> {
+    let res = match name {
+        "void" => "core::ffi::c_void",
+        "bool" | "_Bool" => "bool",
+        "char" => "core::ffi::c_char",
+        "signed char" | "int8_t" => "i8",
+        "unsigned char" | "uint8_t" => "u8",
+        "short" | "short int" | "int16_t" => "i16",
+        "unsigned short" | "unsigned short int" | "uint16_t" => "u16",
+        "int" | "signed int" | "int32_t" => "i32",
+        "unsigned int" | "unsigned" | "uint32_t" => "u32",
+        "long" | "long int" => "core::ffi::c_long",
+        "unsigned long" | "unsigned long int" => "core::ffi::c_ulong",
+        "long long" | "long long int" | "int64_t" => "i64",
+        "unsigned long long" | "unsigned long long int" | "uint64_t" => "u64",
+        "size_t" | "uintptr_t" => "usize",
+        "ssize_t" | "intptr_t" | "ptrdiff_t" => "isize",
+        "float" => "f32",
+        "double" => "f64",
+        _ => return None,
+    };
+    Some(res)
+}
+
+/// Makes a C name usable as a Rust identifier.
+fn rust_ident(name: &str) -> String {
+    let name = name
+        .trim_start_matches("struct ")
+        .trim_start_matches("union ")
+        .trim_start_matches("enum ");
+    let mut res = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    if res.is_empty() || res.starts_with(|c: char| c.is_ascii_digit()) {
+        res.insert(0, '_');
+    }
+    match res.as_str() {
+        // These can't be raw identifiers.
+        "self" | "Self" | "crate" | "super" => format!("{}_", res),
+        "type" | "match" | "ref" | "mod" | "fn" | "impl" | "loop" | "move" | "use" | "where"
+        | "trait" | "in" | "let" | "mut" | "pub" | "as" | "dyn" | "async" | "await" | "try"
+        | "box" | "yield" | "abstract" | "become" | "final" | "macro" | "override" | "priv"
+        | "typeof" | "unsized" | "virtual" | "unsafe" => {
+            format!("r#{}", res)
+        }
+        _ => res,
+    }
+}
+
+impl Function<'_> {
+    /// The prototype recorded for this function.
+    pub fn signature(&self) -> Option<Callable> {
+        self.core.types().callable(&self.name())
+    }
+}
+
+impl Core {
+    /// Sets the prototype of the function at `addr` from a C declaration such
+    /// as `int main(int argc, char **argv)`.
+    ///
+    /// Takes `&mut self` since it rewrites the variables of the function.
+    pub fn set_function_signature(&mut self, addr: u64, sig: &str) -> Result<()> {
+        let csig = CString::new(sig)?;
+        let ok = unsafe {
+            let analysis = (*self.0.as_ptr()).analysis;
+            let fcn = rz_analysis_get_function_at(analysis, addr);
+            if fcn.is_null() {
+                return Err(RizinError::NotFound {
+                    kind: "function",
+                    name: format!("{:#x}", addr),
+                });
+            }
+            rz_analysis_function_set_type_str(analysis, fcn, csig.as_ptr())
+        };
+        if ok {
+            Ok(())
+        } else {
+            Err(RizinError::Parse {
+                what: format!("signature {}", sig),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrapper::*;
+
+    #[test]
+    fn test_parse_struct() {
+        let core = Core::new();
+        let types = core.types();
+        types
+            .parse("struct point { int x; unsigned char tag; long long y; };")
+            .unwrap();
+        let point = types.base_type("point").unwrap();
+        assert_eq!(point.kind, BaseTypeKind::Struct);
+        assert_eq!(
+            point
+                .members
+                .iter()
+                .map(|m| (m.name.as_str(), m.offset, m.size))
+                .collect::<Vec<_>>(),
+            [("x", 0, 4), ("tag", 4, 1), ("y", 8, 8)]
+        );
+        assert_eq!(point.size, 16);
+        assert!(types.structs().iter().any(|s| s.name == "point"));
+        assert_eq!(
+            types.to_rust("point").unwrap(),
+            "#[repr(C)]\npub struct point {\n    pub x: i32,\n    pub tag: u8,\n    pub y: i64,\n}\n"
+        );
+        assert!(types.parse("struct {").is_err());
+    }
+
+    #[test]
+    fn test_to_rust_fn_pointer() {
+        let core = Core::new();
+        let types = core.types();
+        types
+            .parse("struct ops { int (*open)(int); void (*close)(void *, long long); char *dyn; };")
+            .unwrap();
+        assert_eq!(
+            types.to_rust("ops").unwrap(),
+            "#[repr(C)]\npub struct ops {\n    pub open: Option<unsafe extern \"C\" fn(i32) -> i32>,\n    pub close: Option<unsafe extern \"C\" fn(*mut core::ffi::c_void, i64)>,\n    pub r#dyn: *mut core::ffi::c_char,\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_callable() {
+        let core = Core::new();
+        let types = core.types();
+        types.parse("int add(int a, int b);").unwrap();
+        let add = types.callable("add").unwrap();
+        assert_eq!(add.ret.as_deref(), Some("int"));
+        assert_eq!(
+            add.args.iter().map(|a| a.name.as_str()).collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+    }
+
+    #[test]
+    fn test_set_function_signature() {
+        let mut core = Core::new();
+        core.set("analysis.arch", "x86").unwrap();
+        core.set("analysis.bits", "64").unwrap();
+        let io = core.io();
+        io.open("malloc://0x10", Perm::R | Perm::W | Perm::X)
+            .unwrap();
+        // ret
+        io.write_at(0, &[0xc3]).unwrap();
+        drop(io);
+        core.analyze_function(0).unwrap();
+        core.set_function_signature(0, "int f(int x)").unwrap();
+        let sig = core.function_at(0).unwrap().signature().unwrap();
+        assert_eq!(sig.ret.as_deref(), Some("int"));
+        assert!(matches!(
+            core.set_function_signature(0x8, "int g(void)"),
+            Err(RizinError::NotFound { .. })
+        ));
+    }
+}
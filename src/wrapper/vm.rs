@@ -0,0 +1,327 @@
+use std::ffi::CString;
+use std::ptr::{null_mut, NonNull};
+
+use crate::wrapper::{
+    cstr_to_string, AnalysisOp, BitVector, Core, ListRef, PVector, Result, RizinError,
+};
+use crate::*;
+
+/// A value held by an IL variable.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum IlValue {
+    Bool(bool),
+    Bitv(BitVector),
+    /// The raw bits of a float.
+    Float(BitVector),
+}
+
+impl IlValue {
+    unsafe fn from_raw(v: *const RzILVal) -> Result<Self> {
+        let v = v
+            .as_ref()
+            .ok_or(RizinError::NullPointer { what: "il value" })?;
+        match v.type_ {
+            RzILTypePure_RZ_IL_TYPE_PURE_BOOL => {
+                let b = v
+                    .data
+                    .b
+                    .as_ref()
+                    .ok_or(RizinError::NullPointer { what: "il bool" })?;
+                Ok(Self::Bool(b.b))
+            }
+            RzILTypePure_RZ_IL_TYPE_PURE_BITVECTOR => Ok(Self::Bitv(bv(v.data.bv)?)),
+            RzILTypePure_RZ_IL_TYPE_PURE_FLOAT => {
+                let f = v
+                    .data
+                    .f
+                    .as_ref()
+                    .ok_or(RizinError::NullPointer { what: "il float" })?;
+                Ok(Self::Float(bv(f.s)?))
+            }
+            x => Err(RizinError::Unsupported(format!(
+                "unknown il value type {}",
+                x
+            ))),
+        }
+    }
+
+    unsafe fn to_raw(&self) -> Result<*mut RzILVal> {
+        let val = match self {
+            IlValue::Bool(b) => rz_il_value_new_bool(rz_il_bool_new(*b)),
+            IlValue::Bitv(b) => rz_il_value_new_bitv(b.to_raw()?),
+            IlValue::Float(_) => {
+                return Err(RizinError::Unsupported(
+                    "setting float variables is not supported".to_owned(),
+                ))
+            }
+        };
+        if val.is_null() {
+            Err(RizinError::NullPointer { what: "il value" })
+        } else {
+            Ok(val)
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            IlValue::Bool(b) => Some(*b as u64),
+            IlValue::Bitv(b) | IlValue::Float(b) => Some(b.to_u64()),
+        }
+    }
+}
+
+unsafe fn bv(b: *const RzBitVector) -> Result<BitVector> {
+    BitVector::from_raw(b)
+}
+
+/// What happened while executing an op, in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IlEvent {
+    Exception(String),
+    PcWrite {
+        old: u64,
+        new: u64,
+    },
+    MemRead {
+        addr: u64,
+        value: BitVector,
+    },
+    VarRead {
+        name: String,
+        value: IlValue,
+    },
+    MemWrite {
+        addr: u64,
+        old: Option<BitVector>,
+        new: BitVector,
+    },
+    VarWrite {
+        name: String,
+        old: Option<IlValue>,
+        new: IlValue,
+    },
+}
+
+impl IlEvent {
+    unsafe fn from_raw(e: &RzILEvent) -> Result<Self> {
+        let d = &e.data;
+        let res = match e.type_ {
+            RzILEventId_RZ_IL_EVENT_EXCEPTION => {
+                IlEvent::Exception(cstr_to_string(d.exception).unwrap_or_default())
+            }
+            RzILEventId_RZ_IL_EVENT_PC_WRITE => IlEvent::PcWrite {
+                old: rz_bv_to_ut64(d.pc_write.old_pc),
+                new: rz_bv_to_ut64(d.pc_write.new_pc),
+            },
+            RzILEventId_RZ_IL_EVENT_MEM_READ => IlEvent::MemRead {
+                addr: rz_bv_to_ut64(d.mem_read.address),
+                value: bv(d.mem_read.value)?,
+            },
+            RzILEventId_RZ_IL_EVENT_VAR_READ => IlEvent::VarRead {
+                name: cstr_to_string(d.var_read.variable).unwrap_or_default(),
+                value: IlValue::from_raw(d.var_read.value)?,
+            },
+            RzILEventId_RZ_IL_EVENT_MEM_WRITE => IlEvent::MemWrite {
+                addr: rz_bv_to_ut64(d.mem_write.address),
+                old: bv(d.mem_write.old_value).ok(),
+                new: bv(d.mem_write.new_value)?,
+            },
+            RzILEventId_RZ_IL_EVENT_VAR_WRITE => IlEvent::VarWrite {
+                name: cstr_to_string(d.var_write.variable).unwrap_or_default(),
+                old: IlValue::from_raw(d.var_write.old_value).ok(),
+                new: IlValue::from_raw(d.var_write.new_value)?,
+            },
+            x => return Err(RizinError::Unsupported(format!("unknown il event {}", x))),
+        };
+        Ok(res)
+    }
+}
+
+/// The effects of one [`IlVm::step`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepResult {
+    pub events: Vec<IlEvent>,
+    pub pc: u64,
+}
+
+impl StepResult {
+    pub fn reg_writes(&self) -> impl Iterator<Item = (&str, &IlValue)> {
+        self.events.iter().filter_map(|e| match e {
+            IlEvent::VarWrite { name, new, .. } => Some((name.as_str(), new)),
+            _ => None,
+        })
+    }
+
+    pub fn mem_reads(&self) -> impl Iterator<Item = (u64, &BitVector)> {
+        self.events.iter().filter_map(|e| match e {
+            IlEvent::MemRead { addr, value } => Some((*addr, value)),
+            _ => None,
+        })
+    }
+
+    pub fn mem_writes(&self) -> impl Iterator<Item = (u64, &BitVector)> {
+        self.events.iter().filter_map(|e| match e {
+            IlEvent::MemWrite { addr, new, .. } => Some((*addr, new)),
+            _ => None,
+        })
+    }
+
+    pub fn exception(&self) -> Option<&str> {
+        self.events.iter().find_map(|e| match e {
+            IlEvent::Exception(s) => Some(s.as_str()),
+            _ => None,
+        })
+    }
+}
+
+/// An RzIL VM for the arch configured on a [`Core`].
+///
+/// Memory is the core's IO, so map inputs through [`Core::io`] (a custom
+/// [`IoBackend`](crate::wrapper::IoBackend) works for callback-driven memory).
+pub struct IlVm<'a> {
+    core: &'a Core,
+    pub vm: NonNull<RzAnalysisILVM>,
+}
+
+impl Core {
+    pub fn il_vm(&self) -> Result<IlVm<'_>> {
+        let vm = unsafe { rz_analysis_il_vm_new(self.0.as_ref().analysis, null_mut()) };
+        NonNull::new(vm)
+            .map(|vm| IlVm { core: self, vm })
+            .ok_or(RizinError::Unsupported("arch has no il".to_owned()))
+    }
+}
+
+impl Drop for IlVm<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            rz_analysis_il_vm_free(self.vm.as_ptr());
+        }
+    }
+}
+
+impl IlVm<'_> {
+    fn il(&self) -> *mut RzILVM {
+        unsafe { self.vm.as_ref().vm }
+    }
+
+    pub fn pc(&self) -> u64 {
+        unsafe { rz_bv_to_ut64((*self.il()).pc) }
+    }
+
+    pub fn set_pc(&mut self, pc: u64) {
+        unsafe {
+            rz_bv_set_from_ut64((*self.il()).pc, pc);
+        }
+    }
+
+    /// Names of the global variables, i.e. the registers bound from the profile.
+    pub fn reg_names(&self) -> Vec<String> {
+        let vars = unsafe { rz_il_vm_get_all_vars(self.il(), RzILVarKind_RZ_IL_VAR_KIND_GLOBAL) };
+        PVector::<RzILVar>::try_from(vars)
+            .map(|v| {
+                v.iter()
+                    .filter_map(|var| unsafe { cstr_to_string(var.name) })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn reg(&self, name: &str) -> Result<IlValue> {
+        let cname = CString::new(name)?;
+        unsafe {
+            let val = rz_il_vm_get_var_value(
+                self.il(),
+                RzILVarKind_RZ_IL_VAR_KIND_GLOBAL,
+                cname.as_ptr(),
+            );
+            if val.is_null() {
+                return Err(RizinError::NotFound {
+                    kind: "register",
+                    name: name.to_owned(),
+                });
+            }
+            IlValue::from_raw(val)
+        }
+    }
+
+    pub fn set_reg(&mut self, name: &str, value: IlValue) -> Result<()> {
+        let cname = CString::new(name)?;
+        unsafe {
+            let old = rz_il_vm_get_var_value(
+                self.il(),
+                RzILVarKind_RZ_IL_VAR_KIND_GLOBAL,
+                cname.as_ptr(),
+            );
+            if old.is_null() {
+                return Err(RizinError::NotFound {
+                    kind: "register",
+                    name: name.to_owned(),
+                });
+            }
+            rz_il_vm_set_global_var(self.il(), cname.as_ptr(), value.to_raw()?);
+        }
+        Ok(())
+    }
+
+    pub fn read_mem(&self, addr: u64, len: usize) -> Result<Vec<u8>> {
+        let mut buf = vec![0u8; len];
+        self.core.io().read_at(addr, &mut buf)?;
+        Ok(buf)
+    }
+
+    pub fn write_mem(&mut self, addr: u64, data: &[u8]) -> Result<()> {
+        self.core.io().write_at(addr, data)
+    }
+
+    /// Executes the IL of `op` and reports what it touched.
+    pub fn step(&mut self, op: &AnalysisOp) -> Result<StepResult> {
+        let il_op = op.0.il_op;
+        if il_op.is_null() {
+            return Err(RizinError::NullPointer { what: "il_op" });
+        }
+        unsafe {
+            let ok = rz_il_vm_step(self.il(), il_op, op.addr() + op.size() as u64);
+            let events = self.events()?;
+            if !ok && events.iter().all(|e| !matches!(e, IlEvent::Exception(_))) {
+                return Err(RizinError::Failed("il vm step failed".to_owned()));
+            }
+            Ok(StepResult {
+                events,
+                pc: self.pc(),
+            })
+        }
+    }
+
+    unsafe fn events(&self) -> Result<Vec<IlEvent>> {
+        // The event list is owned by the vm.
+        let Some(events) = ListRef::<RzILEvent>::from_raw((*self.il()).events) else {
+            return Ok(vec![]);
+        };
+        events.iter().map(|e| IlEvent::from_raw(e)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrapper::*;
+
+    #[test]
+    fn test_il_vm_step() {
+        let core = Core::new();
+        core.set("analysis.arch", "x86").unwrap();
+        core.set("analysis.bits", "64").unwrap();
+        let mut vm = core.il_vm().unwrap();
+        vm.set_pc(0x1000);
+        vm.set_reg("rax", IlValue::Bitv(BitVector::from_u64(64, 41)))
+            .unwrap();
+        // inc rax
+        let op = core.analysis_op(&[0x48, 0xff, 0xc0], 0x1000).unwrap();
+        let res = vm.step(&op).unwrap();
+        assert_eq!(res.pc, 0x1003);
+        assert!(res
+            .reg_writes()
+            .any(|(r, v)| r == "rax" && v.as_u64() == Some(42)));
+        assert_eq!(vm.reg("rax").unwrap().as_u64(), Some(42));
+    }
+}
@@ -0,0 +1,114 @@
+use crate::wrapper::{Core, List, Result, RizinError};
+use crate::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum XrefKind {
+    Null,
+    Code,
+    Call,
+    Data,
+    String,
+}
+
+impl From<RzAnalysisXRefType> for XrefKind {
+    fn from(value: RzAnalysisXRefType) -> Self {
+        match value {
+            RzAnalysisXRefType_RZ_ANALYSIS_XREF_TYPE_CODE => XrefKind::Code,
+            RzAnalysisXRefType_RZ_ANALYSIS_XREF_TYPE_CALL => XrefKind::Call,
+            RzAnalysisXRefType_RZ_ANALYSIS_XREF_TYPE_DATA => XrefKind::Data,
+            RzAnalysisXRefType_RZ_ANALYSIS_XREF_TYPE_STRING => XrefKind::String,
+            _ => XrefKind::Null,
+        }
+    }
+}
+
+impl From<XrefKind> for RzAnalysisXRefType {
+    fn from(value: XrefKind) -> Self {
+        match value {
+            XrefKind::Null => RzAnalysisXRefType_RZ_ANALYSIS_XREF_TYPE_NULL,
+            XrefKind::Code => RzAnalysisXRefType_RZ_ANALYSIS_XREF_TYPE_CODE,
+            XrefKind::Call => RzAnalysisXRefType_RZ_ANALYSIS_XREF_TYPE_CALL,
+            XrefKind::Data => RzAnalysisXRefType_RZ_ANALYSIS_XREF_TYPE_DATA,
+            XrefKind::String => RzAnalysisXRefType_RZ_ANALYSIS_XREF_TYPE_STRING,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Xref {
+    pub from: u64,
+    pub to: u64,
+    pub kind: XrefKind,
+}
+
+/// Copies the xrefs out of a list returned by rizin, then frees it.
+fn xrefs(list: *mut RzList) -> impl Iterator<Item = Xref> {
+    List::<RzAnalysisXRef>::try_from(list)
+        .map(|l| {
+            l.iter()
+                .map(|x| Xref {
+                    from: x.from,
+                    to: x.to,
+                    kind: x.type_.into(),
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default()
+        .into_iter()
+}
+
+impl Core {
+    /// References pointing to `addr`.
+    pub fn xrefs_to(&self, addr: u64) -> impl Iterator<Item = Xref> {
+        xrefs(unsafe { rz_analysis_xrefs_get_to(self.0.as_ref().analysis, addr) })
+    }
+
+    /// References made by the instruction at `addr`.
+    pub fn xrefs_from(&self, addr: u64) -> impl Iterator<Item = Xref> {
+        xrefs(unsafe { rz_analysis_xrefs_get_from(self.0.as_ref().analysis, addr) })
+    }
+
+    pub fn add_xref(&mut self, xref: Xref) -> Result<()> {
+        let ok = unsafe {
+            rz_analysis_xrefs_set(
+                self.0.as_ref().analysis,
+                xref.from,
+                xref.to,
+                xref.kind.into(),
+            )
+        };
+        if ok {
+            Ok(())
+        } else {
+            Err(RizinError::Xref {
+                from: xref.from,
+                to: xref.to,
+            })
+        }
+    }
+
+    /// Removes the reference from `from` to `to`, returns whether it existed.
+    pub fn remove_xref(&mut self, from: u64, to: u64) -> bool {
+        unsafe { rz_analysis_xref_del(self.0.as_ref().analysis, from, to) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wrapper::*;
+
+    #[test]
+    fn test_xrefs() {
+        let mut core = Core::new();
+        let xref = Xref {
+            from: 0x1000,
+            to: 0x2000,
+            kind: XrefKind::Call,
+        };
+        core.add_xref(xref).unwrap();
+        assert_eq!(core.xrefs_to(0x2000).collect::<Vec<_>>(), [xref]);
+        assert_eq!(core.xrefs_from(0x1000).collect::<Vec<_>>(), [xref]);
+        assert!(core.remove_xref(0x1000, 0x2000));
+        assert_eq!(core.xrefs_to(0x2000).count(), 0);
+    }
+}
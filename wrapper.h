@@ -1,2 +1,6 @@
 #include <librz/rz_core.h>
+#include <librz/rz_crypto.h>
+#include <librz/rz_demangler.h>
+#include <librz/rz_flirt.h>
+#include <librz/rz_hash.h>
 #include <librz/rz_vector.h>